@@ -1,7 +1,78 @@
 use anchor_lang::prelude::*;
 
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+// Ceiling for the deposit and withdraw fees the admin can set (1%)
+#[constant]
+pub const MAX_FEE_BPS: u16 = 100;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Current `VaultState` layout; older accounts must go through migrate_vault
+#[constant]
+pub const VAULT_STATE_VERSION: u8 = 1;
+
 #[constant]
 pub const VAULT_SEED: &[u8] = b"vault";
 
 #[constant]
 pub const VAULT_ACCOUNT_SEED: &[u8] = b"vault_account";
+
+// Stands in for the mint of native SOL vaults; no mint account can live at this address
+pub const NATIVE_MINT: Pubkey = Pubkey::new_from_array([0; 32]);
+
+// Lamports per SOL as a power of ten, the decimals of native vaults
+#[constant]
+pub const NATIVE_DECIMALS: u8 = 9;
+
+// Seconds before a raised or removed withdraw limit takes effect
+#[constant]
+pub const WITHDRAW_LIMIT_RAISE_DELAY: i64 = 86_400;
+
+// Seconds a frozen vault stays frozen unless the owner and guardian unfreeze it together (3 days)
+#[constant]
+pub const UNFREEZE_DELAY: i64 = 259_200;
+
+#[constant]
+pub const WITHDRAW_REQUEST_SEED: &[u8] = b"withdraw_request";
+
+#[constant]
+pub const OWNER_PROPOSAL_SEED: &[u8] = b"owner_proposal";
+
+#[constant]
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+#[constant]
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+
+pub const MAX_ALLOWLIST_DESTINATIONS: usize = 8;
+
+#[constant]
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt_mint";
+
+#[constant]
+pub const POOL_SEED: &[u8] = b"pool";
+
+#[constant]
+pub const POOL_ACCOUNT_SEED: &[u8] = b"pool_account";
+
+#[constant]
+pub const POSITION_SEED: &[u8] = b"position";
+
+#[constant]
+pub const BASKET_SEED: &[u8] = b"basket";
+
+#[constant]
+pub const BASKET_ACCOUNT_SEED: &[u8] = b"basket_account";
+
+// Vaults one batch_deposit or batch_withdraw may touch; bounded by the transaction's account limit
+pub const MAX_BATCH_VAULTS: usize = 10;
+
+// Recipients one payout may pay; bounded by the transaction's account limit
+pub const MAX_PAYOUT_RECIPIENTS: usize = 16;
+
+// Must stay a multiple of 8 to keep `BasketState` free of implicit padding
+pub const MAX_BASKET_MINTS: usize = 8;
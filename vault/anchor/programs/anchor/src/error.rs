@@ -18,4 +18,70 @@ pub enum ErrorCode {
     InvalidArgument,
     #[msg("Custom error message")]
     CustomError,
+    #[msg("Mint has a freeze authority")]
+    MintHasFreezeAuthority,
+    #[msg("Mint decimals outside the allowed range")]
+    MintDecimalsOutOfRange,
+    #[msg("Mint has a permanent delegate")]
+    MintHasPermanentDelegate,
+    #[msg("Mint is non-transferable")]
+    MintNonTransferable,
+    #[msg("Mint has a transfer hook")]
+    MintHasTransferHook,
+    #[msg("Mint creates frozen token accounts by default")]
+    MintDefaultFrozen,
+    #[msg("Receipt mint already exists")]
+    ReceiptMintAlreadyExists,
+    #[msg("Vault has no receipt mint")]
+    ReceiptMintNotEnabled,
+    #[msg("Receipt accounts are required for this vault")]
+    MissingReceiptAccounts,
+    #[msg("Amount too small to be worth a share")]
+    ZeroShares,
+    #[msg("Vault is locked")]
+    VaultLocked,
+    #[msg("Lock can only be extended")]
+    LockNotExtended,
+    #[msg("Cooldown can only be extended")]
+    CooldownNotExtended,
+    #[msg("Vault has a withdraw cooldown, use request_withdraw")]
+    WithdrawRequiresRequest,
+    #[msg("Withdraw request is not ready yet")]
+    CooldownNotElapsed,
+    #[msg("Vault has a pending withdraw request")]
+    PendingWithdrawal,
+    #[msg("Invalid multisig configuration")]
+    InvalidMultisig,
+    #[msg("Not enough multisig signers")]
+    MultisigThresholdNotMet,
+    #[msg("Vault ownership cannot be transferred")]
+    OwnershipTransferUnsupported,
+    #[msg("Basket already holds the maximum number of mints")]
+    BasketFull,
+    #[msg("Withdrawal exceeds the vault's limit for this window")]
+    WithdrawLimitExceeded,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Fee exceeds the program maximum")]
+    FeeTooHigh,
+    #[msg("Treasury account is required while a fee is charged")]
+    MissingTreasuryAccount,
+    #[msg("Vault token account holds less than deposited")]
+    VaultUnderfunded,
+    #[msg("Unsupported vault layout version, migrate the vault first")]
+    UnsupportedVaultVersion,
+    #[msg("Withdrawal destination is not on the vault's allowlist")]
+    DestinationNotAllowed,
+    #[msg("Allowlist account is required for this vault")]
+    MissingAllowlistAccount,
+    #[msg("Allowlist already holds the maximum number of destinations")]
+    AllowlistFull,
+    #[msg("Withdrawal allowlist cannot be used with this vault")]
+    AllowlistUnsupported,
+    #[msg("Vault is frozen")]
+    VaultFrozen,
+    #[msg("Multisig control cannot be used with this vault")]
+    MultisigUnsupported,
+    #[msg("Withdraw cooldown cannot be used with this vault")]
+    CooldownUnsupported,
 }
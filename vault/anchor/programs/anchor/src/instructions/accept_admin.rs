@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::events::AdminTransferredEvent;
+use crate::state::ProgramConfig;
+use crate::error::ErrorCode;
+use crate::CONFIG_SEED;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        has_one = pending_admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let previous_admin = config.admin;
+    config.admin = ctx.accounts.pending_admin.key();
+    config.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferredEvent {
+        previous_admin,
+        new_admin: config.admin,
+    });
+    Ok(())
+}
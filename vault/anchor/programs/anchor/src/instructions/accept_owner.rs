@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use super::transfer_fee::harvest_withheld_fees;
+use crate::events::{OwnershipTransferredEvent, VaultSnapshot};
+use crate::state::{AuditOp, OwnerProposal, ProgramConfig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, OWNER_PROPOSAL_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    /// Current owner; receives the rent of the owner proposal
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user,
+        close = rent_payer,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [OWNER_PROPOSAL_SEED, vault_state.key().as_ref()],
+        bump = owner_proposal.load()?.bump,
+        has_one = new_owner,
+        close = user,
+    )]
+    pub owner_proposal: AccountLoader<'info, OwnerProposal>,
+
+    #[account(
+        init,
+        seeds = [VAULT_SEED, new_owner.key().as_ref(), mint.key().as_ref()],
+        bump,
+        payer = new_owner,
+        space = VaultState::SPACE,
+    )]
+    pub new_vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [VAULT_ACCOUNT_SEED, new_vault_state.key().as_ref()],
+        bump,
+        payer = new_owner,
+        token::mint = mint,
+        token::authority = new_vault_state,
+        token::token_program = token_program,
+    )]
+    pub new_vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Writable so withheld transfer fees can be harvested into it before the vault account closes
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Refunded the rent of the retired vault accounts
+    /// CHECK: only receives lamports; must be the payer recorded in `vault_state`
+    #[account(mut, address = vault_state.load_vault()?.rent_refund() @ ErrorCode::Unauthorized)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+// Vault PDAs are derived from the owner, so the vault is rebuilt under the new owner's
+// seeds and the old one is emptied and closed
+pub fn handle_accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+
+    let user_key = ctx.accounts.user.key();
+    let mint_key = ctx.accounts.mint.key();
+    let old_state = *ctx.accounts.vault_state.load_vault()?;
+    require!(old_state.pending_withdraw == 0, ErrorCode::PendingWithdrawal);
+    require!(!old_state.is_frozen(), ErrorCode::VaultFrozen);
+    // Any of them could have been set up after the proposal was made
+    require!(
+        !old_state.has_receipt_mint() && !old_state.has_multisig() && !old_state.has_allowlist(),
+        ErrorCode::OwnershipTransferUnsupported
+    );
+    old_state.check_backing(ctx.accounts.vault_account.amount)?;
+
+    let amount = ctx.accounts.vault_account.amount;
+
+    let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[old_state.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        to: ctx.accounts.new_vault_account.to_account_info(),
+        authority: ctx.accounts.vault_state.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    harvest_withheld_fees(&ctx.accounts.token_program, &ctx.accounts.mint, &ctx.accounts.vault_account)?;
+
+    let close_accounts = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.rent_payer.to_account_info(),
+        authority: ctx.accounts.vault_state.to_account_info(),
+    };
+    let close_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), close_accounts, signer);
+    close_account(close_ctx)?;
+
+    // Transfer-fee mints deliver less than was sent
+    ctx.accounts.new_vault_account.reload()?;
+    let deposited = old_state.deposited.min(ctx.accounts.new_vault_account.amount);
+
+    let new_state = &mut ctx.accounts.new_vault_state.load_init()?;
+    **new_state = VaultState {
+        user: ctx.accounts.new_owner.key(),
+        bump: ctx.bumps.new_vault_state,
+        bump_token_account: ctx.bumps.new_vault_account,
+        deposited,
+        rent_payer: ctx.accounts.new_owner.key(),
+        // The guardian was chosen by the previous owner; the new owner picks their own
+        guardian: Pubkey::default(),
+        unfreeze_ts: 0,
+        frozen: 0,
+        ..old_state
+    };
+    // The old vault is closed, so its audit chain continues in the new one
+    let snapshot = VaultSnapshot::take(new_state, AuditOp::Transfer, deposited, ctx.accounts.new_vault_account.amount)?;
+
+    emit_cpi!(OwnershipTransferredEvent {
+        previous_owner: user_key,
+        new_owner: ctx.accounts.new_owner.key(),
+        mint: mint_key,
+        amount: deposited,
+        snapshot,
+    });
+    Ok(())
+}
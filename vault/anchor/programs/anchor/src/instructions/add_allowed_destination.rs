@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::events::DestinationAddedEvent;
+use crate::state::{VaultState, VaultStateLoader, WithdrawAllowlist};
+use crate::{ALLOWLIST_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddAllowedDestination<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump = allowlist.load()?.bump,
+    )]
+    pub allowlist: AccountLoader<'info, WithdrawAllowlist>,
+}
+
+// The destination only becomes usable after the allowlist's delay, leaving the owner time to
+// remove it if the key that added it was stolen
+pub fn handle_add_allowed_destination(ctx: Context<AddAllowedDestination>, destination: Pubkey) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let active_ts = ctx.accounts.allowlist.load_mut()?.add(destination, now)?;
+
+    emit_cpi!(DestinationAddedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.vault_state.load_vault()?.mint,
+        destination,
+        active_ts,
+    });
+    Ok(())
+}
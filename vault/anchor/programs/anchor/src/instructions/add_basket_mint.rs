@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::events::BasketMintAddedEvent;
+use crate::state::{BasketState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{BASKET_ACCOUNT_SEED, BASKET_SEED, CONFIG_SEED, MAX_BASKET_MINTS};
+
+#[derive(Accounts)]
+pub struct AddBasketMint<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_SEED, user.key().as_ref()],
+        bump = basket.load()?.bump,
+        has_one = user,
+        has_one = token_program,
+    )]
+    pub basket: AccountLoader<'info, BasketState>,
+
+    #[account(
+        init,
+        seeds = [BASKET_ACCOUNT_SEED, basket.key().as_ref(), mint.key().as_ref()],
+        bump,
+        payer = user,
+        token::mint = mint,
+        token::authority = basket,
+        token::token_program = token_program,
+    )]
+    pub basket_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    // Its mint policy must accept `mint`
+    #[account(seeds = [CONFIG_SEED], bump = config.load()?.bump)]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Appends `mint` at the next free index; indexes never move
+pub fn handle_add_basket_mint(ctx: Context<AddBasketMint>) -> Result<()> {
+    let mut basket = ctx.accounts.basket.load_mut()?;
+    let mint = &ctx.accounts.mint;
+
+    require!(mint.supply > 0, ErrorCode::InvalidMint);
+    ctx.accounts.config.load()?.mint_policy().check(mint)?;
+    require!((basket.mint_count as usize) < MAX_BASKET_MINTS, ErrorCode::BasketFull);
+
+    let index = basket.mint_count;
+    basket.mints[index as usize] = mint.key();
+    basket.bumps_token_account[index as usize] = ctx.bumps.basket_account;
+    basket.mint_count += 1;
+
+    emit!(BasketMintAddedEvent {
+        basket: ctx.accounts.basket.key(),
+        mint: mint.key(),
+        index,
+    });
+    Ok(())
+}
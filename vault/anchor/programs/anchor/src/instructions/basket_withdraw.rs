@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{BasketSnapshot, BasketWithdrawEvent};
+use crate::state::{BasketState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{BASKET_ACCOUNT_SEED, BASKET_SEED, CONFIG_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct BasketWithdraw<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_SEED, user.key().as_ref()],
+        bump = basket.load()?.bump,
+        has_one = user,
+        has_one = token_program,
+    )]
+    pub basket: AccountLoader<'info, BasketState>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_ACCOUNT_SEED, basket.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::token_program = token_program,
+    )]
+    pub basket_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = basket.load()?.index_of(&mint.key()) == Some(index as usize) @ ErrorCode::InvalidMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_basket_withdraw(ctx: Context<BasketWithdraw>, index: u8, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+
+    let basket_bump = {
+        let mut basket = ctx.accounts.basket.load_mut()?;
+        let balance = &mut basket.balances[index as usize];
+        require!(amount <= *balance, ErrorCode::InsufficientBalance);
+        *balance -= amount;
+        basket.bump
+    };
+
+    let user_key = ctx.accounts.user.key();
+    let seeds = &[BASKET_SEED, user_key.as_ref(), &[basket_bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.basket_account.to_account_info(),
+        to: ctx.accounts.user_account.to_account_info(),
+        authority: ctx.accounts.basket.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    let snapshot = BasketSnapshot::take(&mut *ctx.accounts.basket.load_mut()?)?;
+
+    emit_cpi!(BasketWithdrawEvent {
+        basket: ctx.accounts.basket.key(),
+        owner: user_key,
+        mint: ctx.accounts.mint.key(),
+        index,
+        amount,
+        snapshot,
+    });
+    Ok(())
+}
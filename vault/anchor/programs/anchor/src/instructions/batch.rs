@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{MAX_BATCH_VAULTS, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+// One `[vault_state, vault_account, user_account, mint]` group from a batch's remaining accounts,
+// checked the way the `Deposit`/`Withdraw` constraints check their named accounts
+pub struct BatchGroup<'info> {
+    pub vault_state: AccountLoader<'info, VaultState>,
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+impl<'info> BatchGroup<'info> {
+    pub const LEN: usize = 4;
+
+    // Splits the remaining accounts into one group per amount, refusing a vault listed twice
+    pub fn load_all(
+        remaining_accounts: &'info [AccountInfo<'info>],
+        amounts: &[u64],
+        user: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Result<Vec<Self>> {
+        require!(
+            !amounts.is_empty()
+                && amounts.len() <= MAX_BATCH_VAULTS
+                && remaining_accounts.len() == amounts.len() * Self::LEN,
+            ErrorCode::InvalidArgument
+        );
+        let mut groups: Vec<Self> = Vec::with_capacity(amounts.len());
+        for accounts in remaining_accounts.chunks(Self::LEN) {
+            let group = Self::load(accounts, user, token_program)?;
+            require!(
+                groups.iter().all(|other| other.vault_state.key() != group.vault_state.key()),
+                ErrorCode::InvalidArgument
+            );
+            groups.push(group);
+        }
+        Ok(groups)
+    }
+
+    fn load(accounts: &'info [AccountInfo<'info>], user: &Pubkey, token_program: &Pubkey) -> Result<Self> {
+        for info in &accounts[..3] {
+            require!(info.is_writable, anchor_lang::error::ErrorCode::ConstraintMut);
+        }
+        for info in &accounts[1..] {
+            require_keys_eq!(*info.owner, *token_program, anchor_lang::error::ErrorCode::ConstraintTokenTokenProgram);
+        }
+
+        let vault_state = AccountLoader::<VaultState>::try_from(&accounts[0])?;
+        let (vault_user, vault_mint, bump, bump_token_account) = {
+            let state = vault_state.load_vault()?;
+            (state.user, state.mint, state.bump, state.bump_token_account)
+        };
+        require_keys_eq!(vault_user, *user, ErrorCode::Unauthorized);
+        require_keys_eq!(accounts[3].key(), vault_mint, ErrorCode::InvalidMint);
+        let expected = Pubkey::create_program_address(
+            &[VAULT_SEED, user.as_ref(), vault_mint.as_ref(), &[bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidArgument)?;
+        require_keys_eq!(vault_state.key(), expected, ErrorCode::InvalidArgument);
+        let expected = Pubkey::create_program_address(
+            &[VAULT_ACCOUNT_SEED, expected.as_ref(), &[bump_token_account]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidArgument)?;
+        require_keys_eq!(accounts[1].key(), expected, ErrorCode::InvalidArgument);
+
+        let user_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+        require_keys_eq!(user_account.mint, vault_mint, ErrorCode::InvalidMint);
+        require_keys_eq!(user_account.owner, *user, ErrorCode::Unauthorized);
+
+        Ok(Self {
+            vault_state,
+            vault_account: InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?,
+            user_account,
+            mint: InterfaceAccount::<Mint>::try_from(&accounts[3])?,
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TokenInterface, TransferChecked};
+use super::batch::BatchGroup;
+use crate::events::{BatchDepositEvent, BatchEntry, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::CONFIG_SEED;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchDeposit<'info> {
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Remaining accounts: one `[vault_state, vault_account, user_account, mint]` group per amount.
+// Groups carry no receipt or treasury accounts, so vaults with a receipt mint and mints that
+// would be charged a deposit fee are refused; use `deposit` for those.
+pub fn handle_batch_deposit<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchDeposit<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let user_key = ctx.accounts.user.key();
+    let groups = BatchGroup::load_all(ctx.remaining_accounts, &amounts, &user_key, &ctx.accounts.token_program.key())?;
+    let config = *ctx.accounts.config.load()?;
+
+    let mut deposits = Vec::with_capacity(groups.len());
+    for (mut group, amount) in groups.into_iter().zip(amounts) {
+        require!(amount > 0, ErrorCode::InvalidArgument);
+        require!(group.user_account.amount >= amount, ErrorCode::InsufficientBalance);
+        require!(config.deposit_fee(amount) == 0, ErrorCode::MissingTreasuryAccount);
+        require!(!group.vault_state.load_vault()?.has_receipt_mint(), ErrorCode::MissingReceiptAccounts);
+
+        // Credit what arrived, as transfer-fee mints withhold part of it
+        let balance_before = group.vault_account.amount;
+        let cpi_accounts = TransferChecked {
+            from: group.user_account.to_account_info(),
+            to: group.vault_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+            mint: group.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, amount, group.mint.decimals)?;
+
+        group.vault_account.reload()?;
+        let received = group.vault_account.amount.checked_sub(balance_before).ok_or(ErrorCode::MathOverflow)?;
+
+        let mut vault_state = group.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_add(received).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(group.vault_account.amount)?;
+        deposits.push(BatchEntry {
+            mint: group.mint.key(),
+            amount: received,
+            snapshot: VaultSnapshot::take(&mut vault_state, AuditOp::Deposit, received, group.vault_account.amount)?,
+        });
+    }
+
+    emit_cpi!(BatchDepositEvent {
+        owner: user_key,
+        deposits,
+    });
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TokenInterface, TransferChecked};
+use super::batch::BatchGroup;
+use crate::events::{BatchEntry, BatchWithdrawEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchWithdraw<'info> {
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Remaining accounts: one `[vault_state, vault_account, user_account, mint]` group per amount.
+// Every check of `withdraw` applies per vault. Groups carry no receipt, treasury or cosigner
+// accounts, so vaults with a receipt mint or multisig and mints that would be charged a
+// withdraw fee are refused; use `withdraw` for those.
+pub fn handle_batch_withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchWithdraw<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let user_key = ctx.accounts.user.key();
+    let groups = BatchGroup::load_all(ctx.remaining_accounts, &amounts, &user_key, &ctx.accounts.token_program.key())?;
+    let config = *ctx.accounts.config.load()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut withdrawals = Vec::with_capacity(groups.len());
+    for (mut group, amount) in groups.into_iter().zip(amounts) {
+        require!(amount > 0, ErrorCode::InvalidArgument);
+        require!(amount <= group.vault_account.amount, ErrorCode::InsufficientBalance);
+        require!(config.withdraw_fee(amount) == 0, ErrorCode::MissingTreasuryAccount);
+
+        let mint_key = group.mint.key();
+        let vault_state_bump = {
+            let mut vault_state = group.vault_state.load_vault_mut()?;
+            require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+            require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+            require!(vault_state.withdraw_cooldown == 0, ErrorCode::WithdrawRequiresRequest);
+            require!(amount <= vault_state.available(), ErrorCode::InsufficientBalance);
+            require!(!vault_state.has_multisig(), ErrorCode::MultisigThresholdNotMet);
+            require!(!vault_state.has_receipt_mint(), ErrorCode::MissingReceiptAccounts);
+            require!(!vault_state.has_allowlist(), ErrorCode::MissingAllowlistAccount);
+            vault_state.consume_withdraw_limit(amount, now)?;
+            vault_state.bump
+        };
+
+        let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: group.vault_account.to_account_info(),
+            to: group.user_account.to_account_info(),
+            authority: group.vault_state.to_account_info(),
+            mint: group.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, group.mint.decimals)?;
+
+        group.vault_account.reload()?;
+        let mut vault_state = group.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(group.vault_account.amount)?;
+        withdrawals.push(BatchEntry {
+            mint: mint_key,
+            amount,
+            snapshot: VaultSnapshot::take(&mut vault_state, AuditOp::Withdraw, amount, group.vault_account.amount)?,
+        });
+    }
+
+    emit_cpi!(BatchWithdrawEvent {
+        owner: user_key,
+        withdrawals,
+    });
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::{OwnerProposal, VaultState, VaultStateLoader};
+use crate::{OWNER_PROPOSAL_SEED, VAULT_SEED};
+
+#[derive(Accounts)]
+pub struct CancelOwnerProposal<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [OWNER_PROPOSAL_SEED, vault_state.key().as_ref()],
+        bump = owner_proposal.load()?.bump,
+        close = user,
+    )]
+    pub owner_proposal: AccountLoader<'info, OwnerProposal>,
+}
+
+// Closing the proposal is all that is needed; a new one can then be made
+pub fn handle_cancel_owner_proposal(_ctx: Context<CancelOwnerProposal>) -> Result<()> {
+    Ok(())
+}
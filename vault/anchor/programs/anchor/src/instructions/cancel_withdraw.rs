@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::events::WithdrawCancelledEvent;
+use crate::state::{VaultState, VaultStateLoader, WithdrawRequest};
+use crate::error::ErrorCode;
+use crate::{VAULT_SEED, WITHDRAW_REQUEST_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAW_REQUEST_SEED, vault_state.key().as_ref()],
+        bump = withdraw_request.load()?.bump,
+        close = user,
+    )]
+    pub withdraw_request: AccountLoader<'info, WithdrawRequest>,
+}
+
+pub fn handle_cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+    let amount = ctx.accounts.withdraw_request.load()?.amount;
+
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+    vault_state.pending_withdraw = vault_state.pending_withdraw.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    emit_cpi!(WithdrawCancelledEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{BasketSnapshot, CloseBasketEvent};
+use crate::state::{BasketState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{BASKET_ACCOUNT_SEED, BASKET_SEED, CONFIG_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseBasket<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_SEED, user.key().as_ref()],
+        bump = basket.load()?.bump,
+        has_one = user,
+        has_one = token_program,
+        close = user,
+    )]
+    pub basket: AccountLoader<'info, BasketState>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Remaining accounts: one `[basket_account, mint, user_account]` triple per basket mint,
+// in index order. Tokens sent to a basket account outside basket_deposit are swept to
+// `user_account` so they cannot block the close.
+pub fn handle_close_basket<'info>(ctx: Context<'_, '_, 'info, 'info, CloseBasket<'info>>) -> Result<()> {
+
+    let basket_key = ctx.accounts.basket.key();
+    let user_key = ctx.accounts.user.key();
+    let basket = *ctx.accounts.basket.load()?;
+    require!(basket.is_empty(), ErrorCode::NonZeroBalance);
+    require!(
+        ctx.remaining_accounts.len() == basket.mints().len() * 3,
+        ErrorCode::InvalidArgument
+    );
+
+    let seeds = &[BASKET_SEED, user_key.as_ref(), &[basket.bump]];
+    let signer = &[&seeds[..]];
+
+    for (index, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        let mint_key = basket.mints[index];
+        let expected = Pubkey::create_program_address(
+            &[BASKET_ACCOUNT_SEED, basket_key.as_ref(), mint_key.as_ref(), &[basket.bumps_token_account[index]]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidArgument)?;
+        require_keys_eq!(accounts[0].key(), expected, ErrorCode::InvalidArgument);
+        require_keys_eq!(accounts[1].key(), mint_key, ErrorCode::InvalidMint);
+
+        let basket_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[0])?;
+        let mint = InterfaceAccount::<Mint>::try_from(&accounts[1])?;
+
+        if basket_account.amount > 0 {
+            let user_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+            require_keys_eq!(user_account.owner, user_key, ErrorCode::Unauthorized);
+            let cpi_accounts = TransferChecked {
+                from: accounts[0].clone(),
+                to: accounts[2].clone(),
+                authority: ctx.accounts.basket.to_account_info(),
+                mint: accounts[1].clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+            transfer_checked(cpi_ctx, basket_account.amount, mint.decimals)?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: accounts[0].clone(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.basket.to_account_info(),
+        };
+        let close_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), close_accounts, signer);
+        close_account(close_ctx)?;
+    }
+
+    let snapshot = BasketSnapshot::take(&mut *ctx.accounts.basket.load_mut()?)?;
+
+    emit_cpi!(CloseBasketEvent {
+        basket: basket_key,
+        owner: user_key,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::events::{CloseEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, NATIVE_MINT, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseNativeVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Only an empty vault can be closed; the rent goes back to `rent_payer`
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), NATIVE_MINT.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user,
+        close = rent_payer,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    /// CHECK: only receives lamports; must be the payer recorded in `vault_state`
+    #[account(mut, address = vault_state.load_vault()?.rent_refund() @ ErrorCode::Unauthorized)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+// Same rule as close_vault: the owner withdraws first, then closes an empty vault.
+// Lamports sent to `vault_state` outside deposit_native were never credited to the
+// owner and cannot be withdrawn, so they leave with the rent instead of blocking the close.
+pub fn handle_close_native_vault(ctx: Context<CloseNativeVault>) -> Result<()> {
+    let snapshot = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        require!(vault_state.deposited == 0, ErrorCode::NonZeroBalance);
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.user.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        VaultSnapshot::take(&mut vault_state, AuditOp::Close, 0, 0)?
+    };
+
+    emit_cpi!(CloseEvent {
+        owner: ctx.accounts.user.key(),
+        mint: NATIVE_MINT,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::state::{PoolPosition, PoolState};
+use crate::error::ErrorCode;
+use crate::POSITION_SEED;
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool_state.key().as_ref(), user.key().as_ref()],
+        bump = position.load()?.bump,
+        constraint = position.load()?.owner == user.key() @ ErrorCode::Unauthorized,
+        close = user,
+    )]
+    pub position: AccountLoader<'info, PoolPosition>,
+}
+
+pub fn handle_close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    require!(ctx.accounts.position.load()?.shares == 0, ErrorCode::NonZeroBalance);
+    Ok(())
+}
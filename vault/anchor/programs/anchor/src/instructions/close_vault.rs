@@ -1,74 +1,93 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, transfer_checked, CloseAccount, Mint, Token, TokenAccount, TransferChecked};
-use crate::events::CloseEvent;
-use crate::state::VaultState;
-use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED};
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+use super::transfer_fee::harvest_withheld_fees;
+use crate::events::{CloseEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseVault<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(
-        mut,
-        token::mint = vault_state.load()?.mint,
-        token::authority = user,
-    )]
-    pub user_account: Account<'info, TokenAccount>,
-
     #[account(
         mut,
         seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
-        bump = vault_state.load()?.bump,
+        bump = vault_state.load_vault()?.bump,
         has_one = user,
-        close = user,
+        close = rent_payer,
     )]
     pub vault_state: AccountLoader<'info, VaultState>,
 
     #[account(
         mut,
         seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
-        bump = vault_state.load()?.bump_token_account,
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
     )]
-    pub vault_account: Account<'info, TokenAccount>,
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+    // Writable so withheld transfer fees can be harvested into it before the vault account closes
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    /// CHECK: only receives lamports; must be the payer recorded in `vault_state`
+    #[account(mut, address = vault_state.load_vault()?.rent_refund() @ ErrorCode::Unauthorized)]
+    pub rent_payer: UncheckedAccount<'info>,
 }
 
-// Anchor does not require explicit instruction for close,
+// Spec close: the vault must already be empty, sweep_and_close pays out and closes in one go
 pub fn handle_close_vault(ctx: Context<CloseVault>) -> Result<()> {
-    
+
     let user_key = ctx.accounts.user.key();
     let mint_key = ctx.accounts.mint.key();
-    let vault_state_bump = ctx.accounts.vault_state.load()?.bump;
-    let amount = ctx.accounts.vault_account.amount;
-
-    let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
-    let signer = &[&seeds[..]];
-    let cpi_accounts = TransferChecked {
-        from: ctx.accounts.vault_account.to_account_info(),
-        to: ctx.accounts.user_account.to_account_info(),
-        authority: ctx.accounts.vault_state.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
+    let (vault_state_bump, snapshot) = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        require!(
+            vault_state.deposited == 0 && ctx.accounts.vault_account.amount == 0,
+            ErrorCode::NonZeroBalance
+        );
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.user.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        (vault_state.bump, VaultSnapshot::take(&mut vault_state, AuditOp::Close, 0, 0)?)
     };
-    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
-    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
+    harvest_withheld_fees(&ctx.accounts.token_program, &ctx.accounts.mint, &ctx.accounts.vault_account)?;
 
     // Close the token account using the Token Program
+    let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+    let signer = &[&seeds[..]];
     let close_accounts = CloseAccount {
         account: ctx.accounts.vault_account.to_account_info(),
-        destination: ctx.accounts.user.to_account_info(),
+        destination: ctx.accounts.rent_payer.to_account_info(),
         authority: ctx.accounts.vault_state.to_account_info(),
     };
     let close_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), close_accounts, signer);
     close_account(close_ctx)?;
 
-    emit!(CloseEvent {
-        owner: ctx.accounts.user.key(),
-        mint: ctx.accounts.vault_account.mint,
+    emit_cpi!(CloseEvent {
+        owner: user_key,
+        mint: mint_key,
+        snapshot,
     });
     Ok(())
 }
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::events::ReceiptMintCreatedEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{RECEIPT_MINT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateReceiptMint<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump,
+        payer = user,
+        mint::decimals = mint.decimals,
+        mint::authority = vault_state,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_create_receipt_mint(ctx: Context<CreateReceiptMint>) -> Result<()> {
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    require!(!vault_state.has_receipt_mint(), ErrorCode::ReceiptMintAlreadyExists);
+    // Receipts are minted 1:1 with deposits, so existing deposits would be left uncovered
+    require!(vault_state.deposited == 0, ErrorCode::NonZeroBalance);
+    // Receipt holders can redeem to any account, which would get around the allowlist
+    require!(!vault_state.has_allowlist(), ErrorCode::AllowlistUnsupported);
+    // or the vault's multisig signers
+    require!(!vault_state.has_multisig(), ErrorCode::MultisigUnsupported);
+    // redeem has no request step, so a cooldown would leave receipts unredeemable
+    require!(vault_state.withdraw_cooldown == 0, ErrorCode::CooldownUnsupported);
+
+    vault_state.bump_receipt_mint = ctx.bumps.receipt_mint;
+    vault_state.receipt_mint_enabled = 1;
+
+    emit_cpi!(ReceiptMintCreatedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.mint.key(),
+        receipt_mint: ctx.accounts.receipt_mint.key(),
+    });
+    Ok(())
+}
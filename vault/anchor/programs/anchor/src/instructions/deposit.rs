@@ -1,25 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
-use crate::events::DepositEvent;
-use crate::state::VaultState;
+use anchor_spl::token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{DepositEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultState, VaultStateLoader};
 use crate::error::ErrorCode;
-use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED};
+use crate::{CONFIG_SEED, RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     pub user: Signer<'info>,
 
     #[account(
         mut,
-        token::mint = vault_state.load()?.mint,
+        token::mint = vault_state.load_vault()?.mint,
         token::authority = user,
+        token::token_program = token_program,
     )]
-    pub user_account: Account<'info, TokenAccount>,
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
-        bump = vault_state.load()?.bump,
+        bump = vault_state.load_vault()?.bump,
         has_one = user
     )]
     pub vault_state: AccountLoader<'info, VaultState>,
@@ -27,12 +29,46 @@ pub struct Deposit<'info> {
     #[account(
         mut,
         seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
-        bump = vault_state.load()?.bump_token_account,
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
     )]
-    pub vault_account: Account<'info, TokenAccount>,
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Only required once the vault has a receipt mint
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::token_program = token_program,
+    )]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
 pub fn handle_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
@@ -40,6 +76,26 @@ pub fn handle_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidArgument);
     require!(ctx.accounts.user_account.amount >= amount, ErrorCode::InsufficientBalance);
 
+    // Token-2022 transfer-fee mints withhold part of the transfer in the destination
+    // account, so credit what the vault actually received rather than `amount`.
+    let balance_before = ctx.accounts.vault_account.amount;
+
+    // The protocol fee is taken off the top and goes straight to the treasury
+    let fee = ctx.accounts.config.load()?.deposit_fee(amount);
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.user_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
     let cpi_accounts = TransferChecked {
         from: ctx.accounts.user_account.to_account_info(),
         to: ctx.accounts.vault_account.to_account_info(),
@@ -47,15 +103,47 @@ pub fn handle_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         mint: ctx.accounts.mint.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.vault_account.reload()?;
+    let received = ctx.accounts.vault_account.amount.checked_sub(balance_before).ok_or(ErrorCode::MathOverflow)?;
+
+    let (has_receipt_mint, vault_state_bump, snapshot) = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_add(received).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        let snapshot = VaultSnapshot::take(&mut vault_state, AuditOp::Deposit, received, ctx.accounts.vault_account.amount)?;
+        (vault_state.has_receipt_mint(), vault_state.bump, snapshot)
+    };
+
+    // Receipts track `deposited` 1:1
+    if has_receipt_mint {
+        let (Some(receipt_mint), Some(user_receipt_account)) =
+            (&ctx.accounts.receipt_mint, &ctx.accounts.user_receipt_account)
+        else {
+            return err!(ErrorCode::MissingReceiptAccounts);
+        };
 
-    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
-    vault_state.deposited = vault_state.deposited.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let user_key = ctx.accounts.user.key();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+        let signer = &[&seeds[..]];
+        let mint_accounts = MintTo {
+            mint: receipt_mint.to_account_info(),
+            to: user_receipt_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+        let mint_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), mint_accounts, signer);
+        mint_to(mint_ctx, received)?;
+    }
     
-    emit!(DepositEvent {
+    emit_cpi!(DepositEvent {
         owner: ctx.accounts.user.key(),
         mint: ctx.accounts.mint.key(),
-        amount,
+        amount: received,
+        gross_amount: amount,
+        fee,
+        snapshot,
     });
     Ok(())
 }
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{DepositForEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct DepositFor<'info> {
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = vault_state.load_vault()?.mint,
+        token::authority = depositor,
+        token::token_program = token_program,
+    )]
+    pub depositor_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Only required once the vault has a receipt mint
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    // Receipts belong to the beneficiary, not the depositor
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+// Funds `owner`'s vault from the depositor's tokens; only the owner can take them out again
+pub fn handle_deposit_for(ctx: Context<DepositFor>, owner: Pubkey, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+    require!(ctx.accounts.depositor_account.amount >= amount, ErrorCode::InsufficientBalance);
+
+    let balance_before = ctx.accounts.vault_account.amount;
+
+    // The protocol fee is taken off the top and goes straight to the treasury
+    let fee = ctx.accounts.config.load()?.deposit_fee(amount);
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.depositor_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.depositor_account.to_account_info(),
+        to: ctx.accounts.vault_account.to_account_info(),
+        authority: ctx.accounts.depositor.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.vault_account.reload()?;
+    let received = ctx.accounts.vault_account.amount.checked_sub(balance_before).ok_or(ErrorCode::MathOverflow)?;
+
+    let (has_receipt_mint, vault_state_bump, snapshot) = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_add(received).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        let snapshot = VaultSnapshot::take(&mut vault_state, AuditOp::Deposit, received, ctx.accounts.vault_account.amount)?;
+        (vault_state.has_receipt_mint(), vault_state.bump, snapshot)
+    };
+
+    if has_receipt_mint {
+        let (Some(receipt_mint), Some(owner_receipt_account)) =
+            (&ctx.accounts.receipt_mint, &ctx.accounts.owner_receipt_account)
+        else {
+            return err!(ErrorCode::MissingReceiptAccounts);
+        };
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[VAULT_SEED, owner.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+        let signer = &[&seeds[..]];
+        let mint_accounts = MintTo {
+            mint: receipt_mint.to_account_info(),
+            to: owner_receipt_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+        let mint_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), mint_accounts, signer);
+        mint_to(mint_ctx, received)?;
+    }
+
+    emit_cpi!(DepositForEvent {
+        depositor: ctx.accounts.depositor.key(),
+        owner,
+        mint: ctx.accounts.mint.key(),
+        amount: received,
+        gross_amount: amount,
+        fee,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::events::{DepositEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, NATIVE_MINT, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositNative<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), NATIVE_MINT.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_deposit_native(ctx: Context<DepositNative>, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+    require!(ctx.accounts.user.lamports() >= amount, ErrorCode::InsufficientBalance);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user.to_account_info(),
+        to: ctx.accounts.vault_state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    transfer(cpi_ctx, amount)?;
+
+    // Lamports above the rent-exempt minimum are the vault's balance
+    let vault_balance = ctx.accounts.vault_state.get_lamports().saturating_sub(Rent::get()?.minimum_balance(VaultState::SPACE));
+    let snapshot = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        VaultSnapshot::take(&mut vault_state, AuditOp::Deposit, amount, vault_balance)?
+    };
+
+    emit_cpi!(DepositEvent {
+        owner: ctx.accounts.user.key(),
+        mint: NATIVE_MINT,
+        amount,
+        gross_amount: amount,
+        fee: 0, // protocol fees are only charged on token vaults
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::events::AllowlistEnabledEvent;
+use crate::state::{VaultState, VaultStateLoader, WithdrawAllowlist};
+use crate::error::ErrorCode;
+use crate::{ALLOWLIST_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EnableAllowlist<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump,
+        payer = user,
+        space = WithdrawAllowlist::SPACE,
+    )]
+    pub allowlist: AccountLoader<'info, WithdrawAllowlist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Starts empty, so nothing can be withdrawn until a destination has waited out `delay`.
+// Neither the allowlist nor its delay can be turned off again.
+pub fn handle_enable_allowlist(ctx: Context<EnableAllowlist>, delay: i64) -> Result<()> {
+    require!(delay >= 0, ErrorCode::InvalidArgument);
+
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+    // Native withdrawals pay the signer directly, and receipt holders redeem to any account
+    require!(
+        !vault_state.is_native() && !vault_state.has_receipt_mint(),
+        ErrorCode::AllowlistUnsupported
+    );
+    vault_state.allowlist_enabled = 1;
+
+    let allowlist = &mut ctx.accounts.allowlist.load_init()?;
+    allowlist.vault = ctx.accounts.vault_state.key();
+    allowlist.delay = delay;
+    allowlist.bump = ctx.bumps.allowlist;
+
+    emit_cpi!(AllowlistEnabledEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        delay,
+    });
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::events::MultisigEnabledEvent;
+use crate::state::{VaultMultisig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{MAX_MULTISIG_SIGNERS, MULTISIG_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EnableMultisig<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [MULTISIG_SEED, vault_state.key().as_ref()],
+        bump,
+        payer = user,
+        space = VaultMultisig::SPACE,
+    )]
+    pub multisig: AccountLoader<'info, VaultMultisig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Once enabled, withdraw, claim_withdraw, payout, redeem, close_vault, sweep_and_close and the
+// native withdraw and close need `threshold` of `signers`; batch_withdraw refuses the vault
+pub fn handle_enable_multisig(ctx: Context<EnableMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        ErrorCode::InvalidMultisig
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        ErrorCode::InvalidMultisig
+    );
+    for (i, signer) in signers.iter().enumerate() {
+        require!(!signers[..i].contains(signer), ErrorCode::InvalidMultisig);
+    }
+
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+    // Any receipt holder can redeem, so receipts would get around the signers
+    require!(!vault_state.has_receipt_mint(), ErrorCode::MultisigUnsupported);
+    vault_state.multisig_enabled = 1;
+
+    let multisig = &mut ctx.accounts.multisig.load_init()?;
+    multisig.vault = ctx.accounts.vault_state.key();
+    multisig.signers[..signers.len()].copy_from_slice(&signers);
+    multisig.signer_count = signers.len() as u8;
+    multisig.threshold = threshold;
+    multisig.bump = ctx.bumps.multisig;
+
+    emit_cpi!(MultisigEnabledEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        signers,
+        threshold,
+    });
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::events::LockExtendedEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::VAULT_SEED;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+}
+
+// The unlock date can only move later, so a commitment cannot be walked back
+pub fn handle_extend_lock(ctx: Context<ExtendLock>, unlock_ts: i64) -> Result<()> {
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    require!(unlock_ts > vault_state.unlock_ts, ErrorCode::LockNotExtended);
+    vault_state.unlock_ts = unlock_ts;
+
+    emit_cpi!(LockExtendedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        unlock_ts,
+    });
+    Ok(())
+}
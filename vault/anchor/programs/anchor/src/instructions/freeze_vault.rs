@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::events::VaultFrozenEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{UNFREEZE_DELAY, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeVault<'info> {
+    // The owner or the vault's guardian
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.load_vault()?.user.as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+}
+
+// Stops every withdrawal and close without moving funds, e.g. as soon as a key is suspected stolen.
+// Freezing again before an unfreeze fails, so a frozen vault cannot be kept frozen past the delay
+pub fn handle_freeze_vault(ctx: Context<FreezeVault>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    require!(
+        authority == vault_state.user || (vault_state.has_guardian() && authority == vault_state.guardian),
+        ErrorCode::Unauthorized
+    );
+    require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    let unfreeze_ts = now.checked_add(UNFREEZE_DELAY).ok_or(ErrorCode::MathOverflow)?;
+    vault_state.frozen = 1;
+    vault_state.unfreeze_ts = unfreeze_ts;
+
+    emit_cpi!(VaultFrozenEvent {
+        owner: vault_state.user,
+        mint: vault_state.mint,
+        frozen_by: authority,
+        unfreeze_ts,
+    });
+    Ok(())
+}
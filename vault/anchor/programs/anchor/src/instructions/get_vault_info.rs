@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{VaultInfo, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{NATIVE_DECIMALS, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[derive(Accounts)]
+pub struct GetVaultInfo<'info> {
+    #[account(
+        seeds = [VAULT_SEED, vault_state.load_vault()?.user.as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    // Required for token vaults; native vaults have neither
+    #[account(
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+    )]
+    pub vault_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = vault_state.load_vault()?.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+}
+
+// Read-only; Anchor hands the returned struct to `set_return_data` Borsh-encoded
+pub fn handle_get_vault_info(ctx: Context<GetVaultInfo>) -> Result<VaultInfo> {
+    let now = Clock::get()?.unix_timestamp;
+    // A copy, so a pending limit can be shown as applied without writing to the account
+    let mut vault_state = *ctx.accounts.vault_state.load_vault()?;
+    vault_state.apply_pending_limit(now);
+
+    let (vault_balance, decimals) = if vault_state.is_native() {
+        let rent_minimum = Rent::get()?.minimum_balance(VaultState::SPACE);
+        (ctx.accounts.vault_state.get_lamports().saturating_sub(rent_minimum), NATIVE_DECIMALS)
+    } else {
+        match (&ctx.accounts.vault_account, &ctx.accounts.mint) {
+            (Some(vault_account), Some(mint)) => (vault_account.amount, mint.decimals),
+            _ => return err!(ErrorCode::InvalidArgument),
+        }
+    };
+
+    Ok(VaultInfo {
+        version: vault_state.version,
+        owner: vault_state.user,
+        mint: vault_state.mint,
+        deposited: vault_state.deposited,
+        vault_balance,
+        decimals,
+        pending_withdraw: vault_state.pending_withdraw,
+        locked: vault_state.is_locked(now),
+        unlock_ts: vault_state.unlock_ts,
+        withdraw_limit: vault_state.withdraw_limit,
+        withdraw_window: vault_state.withdraw_window,
+        withdraw_limit_remaining: vault_state.withdraw_limit_remaining(now),
+        pending_withdraw_limit: vault_state.pending_withdraw_limit,
+        pending_limit_ts: vault_state.pending_limit_ts,
+        frozen: vault_state.is_frozen(),
+        unfreeze_ts: vault_state.unfreeze_ts,
+    })
+}
@@ -1,21 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::events::InitializeEvent;
-use crate::state::VaultState;
+use crate::events::ConfigInitializedEvent;
+use crate::state::{MintPolicy, ProgramConfig, VaultState};
 use crate::error::ErrorCode;
-use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED};
+use crate::{CONFIG_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED, VAULT_STATE_VERSION};
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction()]
+#[instruction(unlock_ts: i64)]
 pub struct Initialize<'info> {
-    #[account(mut)]
     pub user: Signer<'info>,
 
+    // Funds every account created here and gets the rent back on close; may be `user` itself
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
         seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
         bump,
-        payer = user,
+        payer = payer,
         space = 8 + std::mem::size_of::<VaultState>(),
     )]
     pub vault_state: AccountLoader<'info, VaultState>,
@@ -24,34 +31,109 @@ pub struct Initialize<'info> {
         init,
         seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
         bump,
-        payer = user,
+        payer = payer,
         token::mint = mint,
         token::authority = vault_state,
+        token::token_program = token_program,
     )]
-    pub vault_account: Account<'info, TokenAccount>,
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
+
+    // Created with `user` as admin on the first initialize after deploy; afterwards its
+    // mint policy decides which mints can be vaulted
+    /// CHECK: only created here when still owned by the system program, otherwise loaded as ProgramConfig
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
 }
 
-pub fn handle_initialize(ctx: Context<Initialize>) -> Result<()> {
+// The first initialize after deploy creates the config with its caller as admin. Anyone can be
+// that caller, so run it (or propose_admin to the intended admin) right after deploying, before
+// the program id is public, and check `config.admin` afterwards.
+pub fn handle_initialize(ctx: Context<Initialize>, unlock_ts: i64) -> Result<()> {
+    if ctx.accounts.config.owner == &System::id() {
+        bootstrap_config(
+            ctx.accounts.config.to_account_info(),
+            ctx.accounts.user.key(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.bumps.config,
+        )?;
+    }
+    let policy = load_mint_policy(&ctx.accounts.config)?;
+
     let vault_state = &mut ctx.accounts.vault_state.load_init()?;
     let mint = &ctx.accounts.mint;
 
     // Ensure the mint is initialized and has a non-zero supply
     require!(mint.supply > 0, ErrorCode::InvalidMint);
+    policy.check(mint)?;
     
     vault_state.user = ctx.accounts.user.key();
     vault_state.mint = mint.key();
     vault_state.bump = ctx.bumps.vault_state;
     vault_state.bump_token_account = ctx.bumps.vault_account;
+    vault_state.version = VAULT_STATE_VERSION;
+    vault_state.rent_payer = ctx.accounts.payer.key();
     vault_state.deposited = 0;
+    vault_state.unlock_ts = unlock_ts;
 
-    emit!(InitializeEvent {
+    emit_cpi!(InitializeEvent {
         owner: ctx.accounts.user.key(),
         mint: mint.key(),
     });
     Ok(())
 }
+
+// Creates the config PDA by hand since `init` cannot be made conditional. Lamports
+// already sent to the address are kept, so pre-funding it cannot block the bootstrap.
+fn bootstrap_config<'info>(
+    config: AccountInfo<'info>,
+    admin: Pubkey,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    bump: u8,
+) -> Result<()> {
+    let seeds = &[CONFIG_SEED, &[bump]];
+    let signer = &[&seeds[..]];
+
+    let required = Rent::get()?.minimum_balance(ProgramConfig::SPACE);
+    let top_up = required.saturating_sub(config.lamports());
+    if top_up > 0 {
+        let cpi_accounts = Transfer { from: payer, to: config.clone() };
+        transfer(CpiContext::new(system_program.clone(), cpi_accounts), top_up)?;
+    }
+    let allocate_ctx = CpiContext::new_with_signer(system_program.clone(), Allocate { account_to_allocate: config.clone() }, signer);
+    allocate(allocate_ctx, ProgramConfig::SPACE as u64)?;
+    let assign_ctx = CpiContext::new_with_signer(system_program, Assign { account_to_assign: config.clone() }, signer);
+    assign(assign_ctx, &crate::ID)?;
+
+    let program_config = ProgramConfig {
+        admin,
+        bump,
+        ..Default::default()
+    };
+    let mut data = config.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(ProgramConfig::DISCRIMINATOR);
+    data[8..ProgramConfig::SPACE].copy_from_slice(bytemuck::bytes_of(&program_config));
+
+    emit!(ConfigInitializedEvent {
+        admin,
+    });
+    Ok(())
+}
+
+// The config is an UncheckedAccount so it can be bootstrapped above; check it like AccountLoader would
+fn load_mint_policy(config: &AccountInfo) -> Result<MintPolicy> {
+    require_keys_eq!(*config.owner, crate::ID, anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+    let data = config.try_borrow_data()?;
+    require!(
+        data.len() >= ProgramConfig::SPACE && data[..8] == *ProgramConfig::DISCRIMINATOR,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(bytemuck::pod_read_unaligned::<ProgramConfig>(&data[8..ProgramConfig::SPACE]).mint_policy())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::events::InitializeBasketEvent;
+use crate::state::BasketState;
+use crate::BASKET_SEED;
+
+#[derive(Accounts)]
+pub struct InitializeBasket<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [BASKET_SEED, user.key().as_ref()],
+        bump,
+        payer = user,
+        space = BasketState::SPACE,
+    )]
+    pub basket: AccountLoader<'info, BasketState>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// Mints are added afterwards with add_basket_mint, all under `token_program`
+pub fn handle_initialize_basket(ctx: Context<InitializeBasket>) -> Result<()> {
+    let basket = &mut ctx.accounts.basket.load_init()?;
+
+    basket.user = ctx.accounts.user.key();
+    basket.token_program = ctx.accounts.token_program.key();
+    basket.bump = ctx.bumps.basket;
+
+    emit!(InitializeBasketEvent {
+        basket: ctx.accounts.basket.key(),
+        owner: ctx.accounts.user.key(),
+    });
+    Ok(())
+}
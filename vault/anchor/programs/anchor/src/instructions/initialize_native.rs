@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::events::InitializeEvent;
+use crate::state::{ProgramConfig, VaultState};
+use crate::{CONFIG_SEED, NATIVE_MINT, VAULT_SEED, VAULT_STATE_VERSION};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeNative<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Holds the vaulted lamports on top of its own rent-exempt minimum
+    #[account(
+        init,
+        seeds = [VAULT_SEED, user.key().as_ref(), NATIVE_MINT.as_ref()],
+        bump,
+        payer = user,
+        space = VaultState::SPACE,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+
+    // Its mint policy must accept native SOL's decimals
+    #[account(seeds = [CONFIG_SEED], bump = config.load()?.bump)]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_initialize_native(ctx: Context<InitializeNative>, unlock_ts: i64) -> Result<()> {
+    ctx.accounts.config.load()?.mint_policy().check_native()?;
+
+    let vault_state = &mut ctx.accounts.vault_state.load_init()?;
+
+    vault_state.user = ctx.accounts.user.key();
+    vault_state.mint = NATIVE_MINT;
+    vault_state.bump = ctx.bumps.vault_state;
+    vault_state.version = VAULT_STATE_VERSION;
+    vault_state.rent_payer = ctx.accounts.user.key();
+    vault_state.deposited = 0;
+    vault_state.unlock_ts = unlock_ts;
+
+    emit_cpi!(InitializeEvent {
+        owner: ctx.accounts.user.key(),
+        mint: NATIVE_MINT,
+    });
+    Ok(())
+}
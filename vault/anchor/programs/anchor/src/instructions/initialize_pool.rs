@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::events::InitializePoolEvent;
+use crate::state::{PoolState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, POOL_ACCOUNT_SEED, POOL_SEED};
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PoolState::SPACE,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [POOL_ACCOUNT_SEED, pool_state.key().as_ref()],
+        bump,
+        payer = payer,
+        token::mint = mint,
+        token::authority = pool_state,
+        token::token_program = token_program,
+    )]
+    pub pool_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Its mint policy must accept `mint`
+    #[account(seeds = [CONFIG_SEED], bump = config.load()?.bump)]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Anyone may create the single pool for a mint
+pub fn handle_initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state.load_init()?;
+    let mint = &ctx.accounts.mint;
+
+    require!(mint.supply > 0, ErrorCode::InvalidMint);
+    ctx.accounts.config.load()?.mint_policy().check(mint)?;
+
+    pool_state.mint = mint.key();
+    pool_state.total_shares = 0;
+    pool_state.bump = ctx.bumps.pool_state;
+    pool_state.bump_token_account = ctx.bumps.pool_account;
+
+    emit!(InitializePoolEvent {
+        pool: ctx.accounts.pool_state.key(),
+        mint: mint.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::events::VaultMigratedEvent;
+use crate::state::VaultState;
+use crate::error::ErrorCode;
+use crate::{VAULT_SEED, VAULT_STATE_VERSION};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Older layouts are smaller than `VaultState`, so the account cannot go through `AccountLoader`
+    /// CHECK: discriminator, seeds and owner key are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub vault_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Grows a vault created with an older layout to the current one; the owner pays the extra rent
+pub fn handle_migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+    let vault_state = ctx.accounts.vault_state.to_account_info();
+
+    let (user, mint, bump, version, data_len) = {
+        let data = vault_state.try_borrow_data()?;
+        require!(
+            data.len() >= VaultState::LEGACY_SPACE && data[..8] == *VaultState::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        // The fields up to `bump_token_account` sit at the same offsets in every layout; the baseline's
+        // zeroed padding is where `version` and the flags after `bump_token_account` now live
+        let user = Pubkey::try_from(&data[8..40]).unwrap();
+        let mint = Pubkey::try_from(&data[40..72]).unwrap();
+        let bump = data[8 + std::mem::offset_of!(VaultState, bump)];
+        (user, mint, bump, data[VaultState::VERSION_OFFSET], data.len())
+    };
+
+    require_keys_eq!(user, ctx.accounts.user.key(), ErrorCode::Unauthorized);
+    let expected = Pubkey::create_program_address(&[VAULT_SEED, user.as_ref(), mint.as_ref(), &[bump]], &crate::ID)
+        .map_err(|_| ErrorCode::InvalidArgument)?;
+    require_keys_eq!(expected, vault_state.key(), ErrorCode::InvalidArgument);
+    // Only layouts this program knows how to upgrade; already current vaults have nothing to do
+    require!(version < VAULT_STATE_VERSION, ErrorCode::UnsupportedVaultVersion);
+
+    let rent = Rent::get()?;
+    let top_up = rent.minimum_balance(VaultState::SPACE).saturating_sub(rent.minimum_balance(data_len));
+    if top_up > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: vault_state.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, top_up)?;
+    }
+
+    // New fields start zeroed, which is their default in every layout so far
+    vault_state.realloc(VaultState::SPACE, false)?;
+    vault_state.try_borrow_mut_data()?[VaultState::VERSION_OFFSET] = VAULT_STATE_VERSION;
+
+    emit_cpi!(VaultMigratedEvent {
+        owner: user,
+        mint,
+        from_version: version,
+        to_version: VAULT_STATE_VERSION,
+    });
+    Ok(())
+}
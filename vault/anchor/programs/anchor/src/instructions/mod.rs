@@ -1,9 +1,103 @@
 pub mod initialize;
+pub mod set_mint_policy;
+pub mod set_paused;
+pub mod set_fees;
+pub mod propose_admin;
+pub mod accept_admin;
 pub mod deposit;
+pub mod deposit_for;
 pub mod withdraw;
 pub mod close_vault;
+pub mod sweep_and_close;
+pub mod transfer_fee;
+pub mod reconcile;
+pub mod migrate_vault;
+pub mod top_up_rent;
+pub mod get_vault_info;
+pub mod batch;
+pub mod batch_deposit;
+pub mod batch_withdraw;
+pub mod payout;
+pub mod initialize_native;
+pub mod deposit_native;
+pub mod withdraw_native;
+pub mod close_native_vault;
+pub mod extend_lock;
+pub mod set_withdraw_cooldown;
+pub mod set_withdraw_limit;
+pub mod request_withdraw;
+pub mod claim_withdraw;
+pub mod cancel_withdraw;
+pub mod enable_multisig;
+pub mod enable_allowlist;
+pub mod add_allowed_destination;
+pub mod remove_allowed_destination;
+pub mod set_guardian;
+pub mod freeze_vault;
+pub mod unfreeze_vault;
+pub mod propose_owner;
+pub mod cancel_owner_proposal;
+pub mod accept_owner;
+pub mod create_receipt_mint;
+pub mod redeem;
+pub mod initialize_pool;
+pub mod open_position;
+pub mod pool_deposit;
+pub mod pool_withdraw;
+pub mod close_position;
+pub mod initialize_basket;
+pub mod add_basket_mint;
+pub mod basket_deposit;
+pub mod basket_withdraw;
+pub mod close_basket;
 
 pub use initialize::*;
+pub use set_mint_policy::*;
+pub use set_paused::*;
+pub use set_fees::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
 pub use deposit::*;
+pub use deposit_for::*;
 pub use withdraw::*;
 pub use close_vault::*;
+pub use sweep_and_close::*;
+pub use reconcile::*;
+pub use migrate_vault::*;
+pub use top_up_rent::*;
+pub use get_vault_info::*;
+pub use batch_deposit::*;
+pub use batch_withdraw::*;
+pub use payout::*;
+pub use initialize_native::*;
+pub use deposit_native::*;
+pub use withdraw_native::*;
+pub use close_native_vault::*;
+pub use extend_lock::*;
+pub use set_withdraw_cooldown::*;
+pub use set_withdraw_limit::*;
+pub use request_withdraw::*;
+pub use claim_withdraw::*;
+pub use cancel_withdraw::*;
+pub use enable_multisig::*;
+pub use enable_allowlist::*;
+pub use add_allowed_destination::*;
+pub use remove_allowed_destination::*;
+pub use set_guardian::*;
+pub use freeze_vault::*;
+pub use unfreeze_vault::*;
+pub use propose_owner::*;
+pub use cancel_owner_proposal::*;
+pub use accept_owner::*;
+pub use create_receipt_mint::*;
+pub use redeem::*;
+pub use initialize_pool::*;
+pub use open_position::*;
+pub use pool_deposit::*;
+pub use pool_withdraw::*;
+pub use close_position::*;
+pub use initialize_basket::*;
+pub use add_basket_mint::*;
+pub use basket_deposit::*;
+pub use basket_withdraw::*;
+pub use close_basket::*;
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{PoolPosition, PoolState};
+use crate::POSITION_SEED;
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [POSITION_SEED, pool_state.key().as_ref(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = PoolPosition::SPACE,
+    )]
+    pub position: AccountLoader<'info, PoolPosition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_open_position(ctx: Context<OpenPosition>) -> Result<()> {
+    let position = &mut ctx.accounts.position.load_init()?;
+
+    position.pool = ctx.accounts.pool_state.key();
+    position.owner = ctx.accounts.user.key();
+    position.shares = 0;
+    position.bump = ctx.bumps.position;
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{PayoutEntry, PayoutEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader, WithdrawAllowlist};
+use crate::error::ErrorCode;
+use crate::{ALLOWLIST_SEED, CONFIG_SEED, MAX_PAYOUT_RECIPIENTS, RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Payout<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Only required once the vault has a receipt mint
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only required once the vault has an allowlist
+    #[account(
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump = allowlist.load()?.bump,
+    )]
+    pub allowlist: Option<AccountLoader<'info, WithdrawAllowlist>>,
+}
+
+// Remaining accounts: one recipient token account per amount, in order, followed by the
+// multisig accounts when the vault has one. Each amount is gross, as in withdraw: the withdraw
+// fee on it goes to the treasury and the rest to the recipient, and `deposited` drops once by the total.
+pub fn handle_payout<'info>(ctx: Context<'_, '_, 'info, 'info, Payout<'info>>, amounts: Vec<u64>) -> Result<()> {
+    require!(
+        !amounts.is_empty()
+            && amounts.len() <= MAX_PAYOUT_RECIPIENTS
+            && ctx.remaining_accounts.len() >= amounts.len(),
+        ErrorCode::InvalidArgument
+    );
+    let (recipient_infos, multisig_accounts) = ctx.remaining_accounts.split_at(amounts.len());
+
+    let mut total: u64 = 0;
+    let mut recipients = Vec::with_capacity(amounts.len());
+    for (info, &amount) in recipient_infos.iter().zip(&amounts) {
+        require!(amount > 0, ErrorCode::InvalidArgument);
+        require!(info.is_writable, anchor_lang::error::ErrorCode::ConstraintMut);
+        require_keys_neq!(info.key(), ctx.accounts.vault_account.key(), ErrorCode::InvalidArgument);
+        require_keys_eq!(*info.owner, ctx.accounts.token_program.key(), anchor_lang::error::ErrorCode::ConstraintTokenTokenProgram);
+        let recipient = InterfaceAccount::<TokenAccount>::try_from(info)?;
+        require_keys_eq!(recipient.mint, ctx.accounts.mint.key(), ErrorCode::InvalidMint);
+        total = total.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        recipients.push(recipient);
+    }
+    require!(total <= ctx.accounts.vault_account.amount, ErrorCode::InsufficientBalance);
+    let fees: Vec<u64> = {
+        let config = ctx.accounts.config.load()?;
+        amounts.iter().map(|&amount| config.withdraw_fee(amount)).collect()
+    };
+    let fee: u64 = fees.iter().sum();
+
+    let user_key = ctx.accounts.user.key();
+    let mint_key = ctx.accounts.mint.key();
+    let (vault_state_bump, has_receipt_mint) = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        // Payouts go to third parties, so only an allowlist restricts them
+        if vault_state.has_allowlist() {
+            for recipient in &recipients {
+                WithdrawAllowlist::check_destination(&vault_state, &ctx.accounts.allowlist, &recipient.owner, now)?;
+            }
+        }
+        require!(vault_state.withdraw_cooldown == 0, ErrorCode::WithdrawRequiresRequest);
+        require!(total <= vault_state.available(), ErrorCode::InsufficientBalance);
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(&ctx.accounts.vault_state.key(), &user_key, multisig_accounts)?;
+        }
+        vault_state.consume_withdraw_limit(total, now)?;
+        (vault_state.bump, vault_state.has_receipt_mint())
+    };
+
+    // Outstanding receipts must be burned so they stay backed 1:1 by `deposited`
+    if has_receipt_mint {
+        let (Some(receipt_mint), Some(user_receipt_account)) =
+            (&ctx.accounts.receipt_mint, &ctx.accounts.user_receipt_account)
+        else {
+            return err!(ErrorCode::MissingReceiptAccounts);
+        };
+
+        let burn_accounts = Burn {
+            mint: receipt_mint.to_account_info(),
+            from: user_receipt_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let burn_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+        burn(burn_ctx, total)?;
+    }
+
+    let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+    let signer = &[&seeds[..]];
+
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), fee_accounts, signer);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    for ((recipient, &amount), &fee) in recipients.iter().zip(&amounts).zip(&fees) {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            to: recipient.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
+    }
+
+    ctx.accounts.vault_account.reload()?;
+    let snapshot = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_sub(total).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        VaultSnapshot::take(&mut vault_state, AuditOp::Withdraw, total, ctx.accounts.vault_account.amount)?
+    };
+
+    // One event for the one audit-chain link, so its snapshot sequence matches
+    let payments = recipients
+        .iter()
+        .zip(&amounts)
+        .zip(&fees)
+        .map(|((recipient, &amount), &fee)| PayoutEntry {
+            recipient: recipient.key(),
+            amount: amount - fee,
+            gross_amount: amount,
+            fee,
+        })
+        .collect();
+    emit_cpi!(PayoutEvent {
+        owner: user_key,
+        mint: mint_key,
+        amount: total - fee,
+        gross_amount: total,
+        fee,
+        payments,
+        snapshot,
+    });
+    Ok(())
+}
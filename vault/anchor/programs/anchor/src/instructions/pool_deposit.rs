@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{PoolDepositEvent, PoolSnapshot};
+use crate::state::{PoolPosition, PoolState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, POOL_ACCOUNT_SEED, POOL_SEED, POSITION_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PoolDeposit<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump = pool_state.load()?.bump,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_ACCOUNT_SEED, pool_state.key().as_ref()],
+        bump = pool_state.load()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub pool_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool_state.key().as_ref(), user.key().as_ref()],
+        bump = position.load()?.bump,
+        constraint = position.load()?.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position: AccountLoader<'info, PoolPosition>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_pool_deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+    require!(ctx.accounts.user_account.amount >= amount, ErrorCode::InsufficientBalance);
+
+    // Price shares against the balance before this deposit, and against what actually
+    // arrived in case the mint charges a transfer fee.
+    let total_assets = ctx.accounts.pool_account.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_account.to_account_info(),
+        to: ctx.accounts.pool_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.pool_account.reload()?;
+    let received = ctx.accounts.pool_account.amount.checked_sub(total_assets).ok_or(ErrorCode::MathOverflow)?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let shares = pool_state.convert_to_shares(received, total_assets)?;
+    require!(shares > 0, ErrorCode::ZeroShares);
+
+    pool_state.total_shares = pool_state.total_shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+    let mut position = ctx.accounts.position.load_mut()?;
+    position.shares = position.shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+    let snapshot = PoolSnapshot::take(&mut pool_state, ctx.accounts.pool_account.amount)?;
+
+    emit_cpi!(PoolDepositEvent {
+        pool: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.mint.key(),
+        assets: received,
+        shares,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{PoolSnapshot, PoolWithdrawEvent};
+use crate::state::{PoolPosition, PoolState, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, POOL_ACCOUNT_SEED, POOL_SEED, POSITION_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PoolWithdraw<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump = pool_state.load()?.bump,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [POOL_ACCOUNT_SEED, pool_state.key().as_ref()],
+        bump = pool_state.load()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub pool_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool_state.key().as_ref(), user.key().as_ref()],
+        bump = position.load()?.bump,
+        constraint = position.load()?.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position: AccountLoader<'info, PoolPosition>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Redeems `shares` for their pro-rata part of the pool balance
+pub fn handle_pool_withdraw(ctx: Context<PoolWithdraw>, shares: u64) -> Result<()> {
+
+    require!(shares > 0, ErrorCode::InvalidArgument);
+
+    let total_assets = ctx.accounts.pool_account.amount;
+    let (assets, pool_bump) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let mut position = ctx.accounts.position.load_mut()?;
+        require!(shares <= position.shares, ErrorCode::InsufficientBalance);
+
+        let assets = pool_state.convert_to_assets(shares, total_assets)?;
+        require!(assets > 0, ErrorCode::InvalidArgument);
+
+        position.shares -= shares;
+        pool_state.total_shares = pool_state.total_shares.checked_sub(shares).ok_or(ErrorCode::MathOverflow)?;
+        (assets, pool_state.bump)
+    };
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds = &[POOL_SEED, mint_key.as_ref(), &[pool_bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.pool_account.to_account_info(),
+        to: ctx.accounts.user_account.to_account_info(),
+        authority: ctx.accounts.pool_state.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    transfer_checked(cpi_ctx, assets, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.pool_account.reload()?;
+    let snapshot = PoolSnapshot::take(&mut *ctx.accounts.pool_state.load_mut()?, ctx.accounts.pool_account.amount)?;
+
+    emit_cpi!(PoolWithdrawEvent {
+        pool: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.user.key(),
+        mint: mint_key,
+        assets,
+        shares,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::events::AdminProposedEvent;
+use crate::state::ProgramConfig;
+use crate::error::ErrorCode;
+use crate::CONFIG_SEED;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Proposing again replaces the previous candidate; the default pubkey withdraws it
+pub fn handle_propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.pending_admin = new_admin;
+
+    emit!(AdminProposedEvent {
+        admin: ctx.accounts.admin.key(),
+        new_admin,
+    });
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::events::OwnerProposedEvent;
+use crate::state::{OwnerProposal, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{OWNER_PROPOSAL_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [OWNER_PROPOSAL_SEED, vault_state.key().as_ref()],
+        bump,
+        payer = user,
+        space = OwnerProposal::SPACE,
+    )]
+    pub owner_proposal: AccountLoader<'info, OwnerProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+
+    require_keys_neq!(new_owner, ctx.accounts.user.key(), ErrorCode::InvalidArgument);
+
+    let vault_state = ctx.accounts.vault_state.load_vault()?;
+    // Receipt mint, multisig and allowlist PDAs are derived from the old vault_state and cannot follow it
+    require!(
+        !vault_state.has_receipt_mint() && !vault_state.has_multisig() && !vault_state.has_allowlist(),
+        ErrorCode::OwnershipTransferUnsupported
+    );
+
+    let owner_proposal = &mut ctx.accounts.owner_proposal.load_init()?;
+    owner_proposal.vault = ctx.accounts.vault_state.key();
+    owner_proposal.new_owner = new_owner;
+    owner_proposal.bump = ctx.bumps.owner_proposal;
+
+    emit_cpi!(OwnerProposedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        new_owner,
+    });
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+use crate::events::{ReconcileEvent, VaultSnapshot};
+use crate::state::{AuditOp, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Reconcile<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Only required once the vault has a receipt mint
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+// Credits tokens sent straight to the vault account, bringing `deposited` back in line with the balance
+pub fn handle_reconcile(ctx: Context<Reconcile>) -> Result<()> {
+
+    let (surplus, deposited, has_receipt_mint, vault_state_bump, snapshot) = {
+        let vault_balance = ctx.accounts.vault_account.amount;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.check_backing(vault_balance)?;
+        let surplus = vault_balance - vault_state.deposited;
+        require!(surplus > 0, ErrorCode::InvalidArgument);
+        vault_state.deposited = vault_balance;
+        let snapshot = VaultSnapshot::take(&mut vault_state, AuditOp::Reconcile, surplus, vault_balance)?;
+        (surplus, vault_state.deposited, vault_state.has_receipt_mint(), vault_state.bump, snapshot)
+    };
+
+    // Receipts track `deposited` 1:1, so the owner is issued receipts for the surplus
+    if has_receipt_mint {
+        let (Some(receipt_mint), Some(user_receipt_account)) =
+            (&ctx.accounts.receipt_mint, &ctx.accounts.user_receipt_account)
+        else {
+            return err!(ErrorCode::MissingReceiptAccounts);
+        };
+
+        let user_key = ctx.accounts.user.key();
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+        let signer = &[&seeds[..]];
+        let mint_accounts = MintTo {
+            mint: receipt_mint.to_account_info(),
+            to: user_receipt_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+        let mint_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), mint_accounts, signer);
+        mint_to(mint_ctx, surplus)?;
+    }
+
+    emit_cpi!(ReconcileEvent {
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.mint.key(),
+        surplus,
+        deposited,
+        snapshot,
+    });
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{RedeemEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = holder,
+        token::token_program = token_program,
+    )]
+    pub holder_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = holder,
+        token::token_program = token_program,
+    )]
+    pub holder_receipt_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.load_vault()?.user.as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+// Any receipt holder can burn receipts to pull the same amount of underlying out of the vault
+pub fn handle_redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+    require!(amount <= ctx.accounts.vault_account.amount, ErrorCode::InsufficientBalance);
+
+    let (owner, vault_state_bump) = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(vault_state.has_receipt_mint(), ErrorCode::ReceiptMintNotEnabled);
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        require!(vault_state.withdraw_cooldown == 0, ErrorCode::WithdrawRequiresRequest);
+        require!(amount <= vault_state.available(), ErrorCode::InsufficientBalance);
+        // create_receipt_mint and enable_multisig keep the two apart; should a vault still carry
+        // both, redeeming takes the same approval as withdraw
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.holder.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        vault_state.consume_withdraw_limit(amount, now)?;
+        (vault_state.user, vault_state.bump)
+    };
+
+    let burn_accounts = Burn {
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        from: ctx.accounts.holder_receipt_account.to_account_info(),
+        authority: ctx.accounts.holder.to_account_info(),
+    };
+    let burn_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+    burn(burn_ctx, amount)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds = &[VAULT_SEED, owner.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+    let signer = &[&seeds[..]];
+
+    // `deposited` drops by the gross amount; the fee part is paid to the treasury
+    let fee = ctx.accounts.config.load()?.withdraw_fee(amount);
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), fee_accounts, signer);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        to: ctx.accounts.holder_account.to_account_info(),
+        authority: ctx.accounts.vault_state.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.vault_account.reload()?;
+    let snapshot = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        VaultSnapshot::take(&mut vault_state, AuditOp::Withdraw, amount, ctx.accounts.vault_account.amount)?
+    };
+
+    emit_cpi!(RedeemEvent {
+        owner,
+        holder: ctx.accounts.holder.key(),
+        mint: mint_key,
+        amount: amount - fee,
+        gross_amount: amount,
+        fee,
+        snapshot,
+    });
+    Ok(())
+}
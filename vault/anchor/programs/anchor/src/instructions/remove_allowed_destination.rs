@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::events::DestinationRemovedEvent;
+use crate::state::{VaultState, VaultStateLoader, WithdrawAllowlist};
+use crate::{ALLOWLIST_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveAllowedDestination<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump = allowlist.load()?.bump,
+    )]
+    pub allowlist: AccountLoader<'info, WithdrawAllowlist>,
+}
+
+// Removal applies at once, including for destinations still waiting out their delay
+pub fn handle_remove_allowed_destination(ctx: Context<RemoveAllowedDestination>, destination: Pubkey) -> Result<()> {
+    ctx.accounts.allowlist.load_mut()?.remove(&destination)?;
+
+    emit_cpi!(DestinationRemovedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.vault_state.load_vault()?.mint,
+        destination,
+    });
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::events::WithdrawRequestedEvent;
+use crate::state::{VaultState, VaultStateLoader, WithdrawRequest};
+use crate::error::ErrorCode;
+use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED, WITHDRAW_REQUEST_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    // Only read, to check the amount being reserved is actually held
+    #[account(
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        seeds = [WITHDRAW_REQUEST_SEED, vault_state.key().as_ref()],
+        bump,
+        payer = user,
+        space = WithdrawRequest::SPACE,
+    )]
+    pub withdraw_request: AccountLoader<'info, WithdrawRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Reserves `amount` so it cannot also leave through another path while the request is open
+pub fn handle_request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+    require!(amount <= vault_state.available(), ErrorCode::InsufficientBalance);
+    vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+
+    let ready_ts = Clock::get()?
+        .unix_timestamp
+        .checked_add(vault_state.withdraw_cooldown)
+        .ok_or(ErrorCode::MathOverflow)?;
+    vault_state.pending_withdraw = vault_state.pending_withdraw.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let withdraw_request = &mut ctx.accounts.withdraw_request.load_init()?;
+    withdraw_request.vault = ctx.accounts.vault_state.key();
+    withdraw_request.amount = amount;
+    withdraw_request.ready_ts = ready_ts;
+    withdraw_request.bump = ctx.bumps.withdraw_request;
+
+    emit_cpi!(WithdrawRequestedEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        amount,
+        ready_ts,
+    });
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::events::FeesSetEvent;
+use crate::state::ProgramConfig;
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, MAX_FEE_BPS};
+
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_set_fees(ctx: Context<SetFees>, deposit_fee_bps: u16, withdraw_fee_bps: u16, treasury: Pubkey) -> Result<()> {
+    require!(deposit_fee_bps <= MAX_FEE_BPS && withdraw_fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+    require!(treasury != Pubkey::default(), ErrorCode::InvalidArgument);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.deposit_fee_bps = deposit_fee_bps;
+    config.withdraw_fee_bps = withdraw_fee_bps;
+    config.treasury = treasury;
+
+    emit!(FeesSetEvent {
+        admin: ctx.accounts.admin.key(),
+        deposit_fee_bps,
+        withdraw_fee_bps,
+        treasury,
+    });
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::events::GuardianSetEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::VAULT_SEED;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+}
+
+// `Pubkey::default()` removes the guardian. Fixed while frozen, or whoever holds the owner key
+// could name a guardian of their own and co-sign the early unfreeze with it
+pub fn handle_set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+    require_keys_neq!(guardian, vault_state.user, ErrorCode::InvalidArgument);
+    vault_state.guardian = guardian;
+
+    emit_cpi!(GuardianSetEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        guardian,
+    });
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::events::MintPolicySetEvent;
+use crate::state::{MintPolicy, ProgramConfig};
+use crate::error::ErrorCode;
+use crate::CONFIG_SEED;
+
+#[derive(Accounts)]
+pub struct SetMintPolicy<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+// Only applies to vaults initialized afterwards; existing vaults are left as they are
+pub fn handle_set_mint_policy(ctx: Context<SetMintPolicy>, policy: MintPolicy) -> Result<()> {
+    require!(policy.min_decimals <= policy.max_decimals, ErrorCode::InvalidArgument);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.set_mint_policy(&policy);
+
+    emit!(MintPolicySetEvent {
+        admin: ctx.accounts.admin.key(),
+        policy,
+    });
+    Ok(())
+}
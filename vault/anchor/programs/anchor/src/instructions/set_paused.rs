@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::events::PausedSetEvent;
+use crate::state::ProgramConfig;
+use crate::error::ErrorCode;
+use crate::CONFIG_SEED;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.paused = paused as u8;
+
+    emit!(PausedSetEvent {
+        admin: ctx.accounts.admin.key(),
+        paused,
+    });
+    Ok(())
+}
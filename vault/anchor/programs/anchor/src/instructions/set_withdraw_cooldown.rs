@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::events::WithdrawCooldownSetEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::VAULT_SEED;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetWithdrawCooldown<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+}
+
+// Like the lock, the cooldown only grows; a stolen key must not be able to shorten it
+pub fn handle_set_withdraw_cooldown(ctx: Context<SetWithdrawCooldown>, cooldown: i64) -> Result<()> {
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    // claim_withdraw only pays out SPL tokens
+    require!(!vault_state.is_native(), ErrorCode::InvalidMint);
+    // redeem has no request step, so a cooldown would leave receipts unredeemable
+    require!(!vault_state.has_receipt_mint(), ErrorCode::CooldownUnsupported);
+    require!(cooldown > vault_state.withdraw_cooldown, ErrorCode::CooldownNotExtended);
+    vault_state.withdraw_cooldown = cooldown;
+
+    emit_cpi!(WithdrawCooldownSetEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        cooldown,
+    });
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::events::WithdrawLimitSetEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{VAULT_SEED, WITHDRAW_LIMIT_RAISE_DELAY};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetWithdrawLimit<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+}
+
+// Tightening applies at once; raising or removing the limit (`limit == 0`) waits
+// `WITHDRAW_LIMIT_RAISE_DELAY` so a leaked key cannot lift it and drain the vault.
+pub fn handle_set_withdraw_limit(ctx: Context<SetWithdrawLimit>, limit: u64, window: i64) -> Result<()> {
+
+    require!(limit == 0 || window > 0, ErrorCode::InvalidArgument);
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+    vault_state.apply_pending_limit(now);
+
+    let effective_ts = if vault_state.is_tighter_limit(limit, window) {
+        vault_state.withdraw_limit = limit;
+        vault_state.withdraw_window = window;
+        // A tighter limit also replaces any raise still waiting
+        vault_state.pending_withdraw_limit = 0;
+        vault_state.pending_withdraw_window = 0;
+        vault_state.pending_limit_ts = 0;
+        now
+    } else {
+        let effective_ts = now.checked_add(WITHDRAW_LIMIT_RAISE_DELAY).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.pending_withdraw_limit = limit;
+        vault_state.pending_withdraw_window = window;
+        vault_state.pending_limit_ts = effective_ts;
+        effective_ts
+    };
+
+    emit_cpi!(WithdrawLimitSetEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        limit,
+        window,
+        effective_ts,
+    });
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use super::transfer_fee::harvest_withheld_fees;
+use crate::events::{SweepAndCloseEvent, VaultSnapshot};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader, WithdrawAllowlist};
+use crate::error::ErrorCode;
+use crate::{ALLOWLIST_SEED, CONFIG_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepAndClose<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // The owner's own account, or an allowlisted wallet's once the vault has an allowlist
+    #[account(
+        mut,
+        token::mint = vault_state.load_vault()?.mint,
+        token::token_program = token_program,
+    )]
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user,
+        close = rent_payer,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Writable so withheld transfer fees can be harvested into it before the vault account closes
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only required once the vault has an allowlist
+    #[account(
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump = allowlist.load()?.bump,
+    )]
+    pub allowlist: Option<AccountLoader<'info, WithdrawAllowlist>>,
+
+    /// CHECK: only receives lamports; must be the payer recorded in `vault_state`
+    #[account(mut, address = vault_state.load_vault()?.rent_refund() @ ErrorCode::Unauthorized)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+// Pays out whatever the vault account holds, then closes both accounts
+pub fn handle_sweep_and_close(ctx: Context<SweepAndClose>) -> Result<()> {
+
+    let user_key = ctx.accounts.user.key();
+    let mint_key = ctx.accounts.mint.key();
+    let (vault_state_bump, snapshot) = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        WithdrawAllowlist::check_destination(&vault_state, &ctx.accounts.allowlist, &ctx.accounts.user_account.owner, now)?;
+        require!(vault_state.pending_withdraw == 0, ErrorCode::PendingWithdrawal);
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.user.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        // With a cooldown set, funds only leave through request_withdraw/claim_withdraw
+        require!(
+            vault_state.withdraw_cooldown == 0 || ctx.accounts.vault_account.amount == 0,
+            ErrorCode::WithdrawRequiresRequest
+        );
+        // Sweeping would strand whoever holds outstanding receipts
+        require!(!vault_state.has_receipt_mint() || vault_state.deposited == 0, ErrorCode::NonZeroBalance);
+        // Closing sweeps the balance, so it must fit in the withdraw limit too
+        vault_state.consume_withdraw_limit(ctx.accounts.vault_account.amount, now)?;
+        // Everything leaves the vault
+        vault_state.deposited = 0;
+        (vault_state.bump, VaultSnapshot::take(&mut vault_state, AuditOp::Close, ctx.accounts.vault_account.amount, 0)?)
+    };
+    let amount = ctx.accounts.vault_account.amount;
+
+    let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
+    let signer = &[&seeds[..]];
+
+    // `deposited` drops by the gross amount; the fee part is paid to the treasury
+    let fee = ctx.accounts.config.load()?.withdraw_fee(amount);
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), fee_accounts, signer);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        to: ctx.accounts.user_account.to_account_info(),
+        authority: ctx.accounts.vault_state.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
+
+
+    harvest_withheld_fees(&ctx.accounts.token_program, &ctx.accounts.mint, &ctx.accounts.vault_account)?;
+
+    // Close the token account using the Token Program
+    let close_accounts = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.rent_payer.to_account_info(),
+        authority: ctx.accounts.vault_state.to_account_info(),
+    };
+    let close_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), close_accounts, signer);
+    close_account(close_ctx)?;
+
+    emit_cpi!(SweepAndCloseEvent {
+        owner: ctx.accounts.user.key(),
+        mint: ctx.accounts.vault_account.mint,
+        amount: amount - fee,
+        gross_amount: amount,
+        fee,
+        snapshot,
+    });
+    Ok(())
+}
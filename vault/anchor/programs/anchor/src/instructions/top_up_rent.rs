@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::TokenAccount;
+use crate::events::RentToppedUpEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TopUpRent<'info> {
+    // Anyone may pay; the recorded rent payer stays the vault's creator
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.load_vault()?.user.as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    // Native vaults have no token account
+    #[account(
+        mut,
+        seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_token_account,
+    )]
+    pub vault_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Brings the vault accounts back to the current rent-exempt minimum
+pub fn handle_top_up_rent(ctx: Context<TopUpRent>) -> Result<()> {
+    let rent = Rent::get()?;
+
+    // Native vaults keep their deposits as lamports on top of the rent
+    let (held, user, mint) = {
+        let vault_state = ctx.accounts.vault_state.load_vault()?;
+        if let Some(vault_account) = &ctx.accounts.vault_account {
+            vault_state.check_backing(vault_account.amount)?;
+        }
+        let held = if vault_state.is_native() { vault_state.deposited } else { 0 };
+        (held, vault_state.user, vault_state.mint)
+    };
+    let vault_state_info = ctx.accounts.vault_state.to_account_info();
+    let mut top_ups = vec![(
+        vault_state_info.clone(),
+        (rent.minimum_balance(vault_state_info.data_len()) + held).saturating_sub(vault_state_info.lamports()),
+    )];
+    if let Some(vault_account) = &ctx.accounts.vault_account {
+        let vault_account_info = vault_account.to_account_info();
+        let minimum = rent.minimum_balance(vault_account_info.data_len());
+        top_ups.push((vault_account_info.clone(), minimum.saturating_sub(vault_account_info.lamports())));
+    }
+
+    let mut amount: u64 = 0;
+    for (account, lamports) in top_ups {
+        if lamports == 0 {
+            continue;
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: account,
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, lamports)?;
+        amount = amount.checked_add(lamports).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    emit_cpi!(RentToppedUpEvent {
+        payer: ctx.accounts.payer.key(),
+        owner: user,
+        mint,
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// Token-2022 refuses to close an account that still holds withheld transfer fees, which a vault
+// collects on every deposit of a transfer-fee mint. Harvesting is permissionless and moves them
+// to the mint, where the mint's withdraw authority can claim them.
+pub fn harvest_withheld_fees<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault_account: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    let vault_info = vault_account.to_account_info();
+    // Legacy SPL-Token accounts carry no extensions
+    if *vault_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let withheld = {
+        let data = vault_info.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee_amount| u64::from(fee_amount.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: token_program.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    harvest_withheld_tokens_to_mint(cpi_ctx, vec![vault_info])
+}
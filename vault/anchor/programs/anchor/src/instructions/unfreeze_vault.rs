@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::events::VaultUnfrozenEvent;
+use crate::state::{VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::VAULT_SEED;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnfreezeVault<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), vault_state.load_vault()?.mint.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    // Only needed to unfreeze before `unfreeze_ts`
+    pub guardian: Option<Signer<'info>>,
+}
+
+// The owner alone once `UNFREEZE_DELAY` has passed since the freeze, or earlier with the guardian
+pub fn handle_unfreeze_vault(ctx: Context<UnfreezeVault>) -> Result<()> {
+    let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+
+    require!(vault_state.is_frozen(), ErrorCode::InvalidArgument);
+    let guardian_signed = match &ctx.accounts.guardian {
+        Some(guardian) => {
+            require!(
+                vault_state.has_guardian() && guardian.key() == vault_state.guardian,
+                ErrorCode::Unauthorized
+            );
+            true
+        }
+        None => {
+            let now = Clock::get()?.unix_timestamp;
+            require!(now >= vault_state.unfreeze_ts, ErrorCode::VaultFrozen);
+            false
+        }
+    };
+    vault_state.frozen = 0;
+    vault_state.unfreeze_ts = 0;
+
+    emit_cpi!(VaultUnfrozenEvent {
+        owner: ctx.accounts.user.key(),
+        mint: vault_state.mint,
+        guardian_signed,
+    });
+    Ok(())
+}
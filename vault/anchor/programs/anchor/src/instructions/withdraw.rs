@@ -1,25 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
-use crate::events::WithdrawEvent;
-use crate::state::VaultState;
+use anchor_spl::token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::events::{VaultSnapshot, WithdrawEvent};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader, WithdrawAllowlist};
 use crate::error::ErrorCode;
-use crate::{VAULT_ACCOUNT_SEED, VAULT_SEED};
+use crate::{ALLOWLIST_SEED, CONFIG_SEED, RECEIPT_MINT_SEED, VAULT_ACCOUNT_SEED, VAULT_SEED};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     pub user: Signer<'info>,
 
+    // The owner's own account, or an allowlisted wallet's once the vault has an allowlist
     #[account(
         mut,
-        token::mint = vault_state.load()?.mint,
-        token::authority = user,
+        token::mint = vault_state.load_vault()?.mint,
+        token::token_program = token_program,
     )]
-    pub user_account: Account<'info, TokenAccount>,
+    pub user_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [VAULT_SEED, user.key().as_ref(), mint.key().as_ref()],
-        bump = vault_state.load()?.bump,
+        bump = vault_state.load_vault()?.bump,
         has_one = user
     )]
     pub vault_state: AccountLoader<'info, VaultState>,
@@ -27,12 +29,54 @@ pub struct Withdraw<'info> {
     #[account(
         mut,
         seeds = [VAULT_ACCOUNT_SEED, vault_state.key().as_ref()],
-        bump = vault_state.load()?.bump_token_account,
+        bump = vault_state.load_vault()?.bump_token_account,
+        token::token_program = token_program,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    // Only required once the vault has a receipt mint
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, vault_state.key().as_ref()],
+        bump = vault_state.load_vault()?.bump_receipt_mint,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
     )]
-    pub vault_account: Account<'info, TokenAccount>,
+    pub config: AccountLoader<'info, ProgramConfig>,
 
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+    // Only required while the config charges a fee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.load()?.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only required once the vault has an allowlist
+    #[account(
+        seeds = [ALLOWLIST_SEED, vault_state.key().as_ref()],
+        bump = allowlist.load()?.bump,
+    )]
+    pub allowlist: Option<AccountLoader<'info, WithdrawAllowlist>>,
 }
 
 pub fn handle_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
@@ -42,10 +86,61 @@ pub fn handle_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
 
     let user_key = ctx.accounts.user.key();
     let mint_key = ctx.accounts.mint.key();
-    let vault_state_bump = ctx.accounts.vault_state.load()?.bump;
+    let (vault_state_bump, has_receipt_mint) = {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        WithdrawAllowlist::check_destination(&vault_state, &ctx.accounts.allowlist, &ctx.accounts.user_account.owner, now)?;
+        require!(vault_state.withdraw_cooldown == 0, ErrorCode::WithdrawRequiresRequest);
+        require!(amount <= vault_state.available(), ErrorCode::InsufficientBalance);
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.user.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        vault_state.consume_withdraw_limit(amount, now)?;
+        (vault_state.bump, vault_state.has_receipt_mint())
+    };
+
+    // Outstanding receipts must be burned so they stay backed 1:1 by `deposited`
+    if has_receipt_mint {
+        let (Some(receipt_mint), Some(user_receipt_account)) =
+            (&ctx.accounts.receipt_mint, &ctx.accounts.user_receipt_account)
+        else {
+            return err!(ErrorCode::MissingReceiptAccounts);
+        };
+
+        let burn_accounts = Burn {
+            mint: receipt_mint.to_account_info(),
+            from: user_receipt_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let burn_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+        burn(burn_ctx, amount)?;
+    }
 
     let seeds = &[VAULT_SEED, user_key.as_ref(), mint_key.as_ref(), &[vault_state_bump]];
     let signer = &[&seeds[..]];
+
+    // `deposited` drops by the gross amount; the fee part is paid to the treasury
+    let fee = ctx.accounts.config.load()?.withdraw_fee(amount);
+    if fee > 0 {
+        let Some(treasury_account) = &ctx.accounts.treasury_account else {
+            return err!(ErrorCode::MissingTreasuryAccount);
+        };
+        let fee_accounts = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let fee_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), fee_accounts, signer);
+        transfer_checked(fee_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
     let cpi_accounts = TransferChecked {
         from: ctx.accounts.vault_account.to_account_info(),
         to: ctx.accounts.user_account.to_account_info(),
@@ -53,15 +148,23 @@ pub fn handle_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         mint: ctx.accounts.mint.to_account_info(),
     };
     let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
-    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    transfer_checked(cpi_ctx, amount - fee, ctx.accounts.mint.decimals)?;
 
-    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
-    vault_state.deposited = vault_state.deposited.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.vault_account.reload()?;
+    let snapshot = {
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        vault_state.deposited = vault_state.deposited.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        vault_state.check_backing(ctx.accounts.vault_account.amount)?;
+        VaultSnapshot::take(&mut vault_state, AuditOp::Withdraw, amount, ctx.accounts.vault_account.amount)?
+    };
 
-    emit!(WithdrawEvent {
+    emit_cpi!(WithdrawEvent {
         owner: ctx.accounts.user.key(),
         mint: ctx.accounts.user_account.mint,
-        amount,
+        amount: amount - fee,
+        gross_amount: amount,
+        fee,
+        snapshot,
     });
     Ok(())
 }
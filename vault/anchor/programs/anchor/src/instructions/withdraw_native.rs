@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::events::{VaultSnapshot, WithdrawEvent};
+use crate::state::{AuditOp, ProgramConfig, VaultMultisig, VaultState, VaultStateLoader};
+use crate::error::ErrorCode;
+use crate::{CONFIG_SEED, NATIVE_MINT, VAULT_SEED};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawNative<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user.key().as_ref(), NATIVE_MINT.as_ref()],
+        bump = vault_state.load_vault()?.bump,
+        has_one = user
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.load()?.bump,
+        constraint = !config.load()?.is_paused() @ ErrorCode::ProgramPaused,
+    )]
+    pub config: AccountLoader<'info, ProgramConfig>,
+}
+
+pub fn handle_withdraw_native(ctx: Context<WithdrawNative>, amount: u64) -> Result<()> {
+
+    require!(amount > 0, ErrorCode::InvalidArgument);
+
+    {
+        let now = Clock::get()?.unix_timestamp;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        require!(!vault_state.is_frozen(), ErrorCode::VaultFrozen);
+        require!(!vault_state.is_locked(now), ErrorCode::VaultLocked);
+        require!(amount <= vault_state.available(), ErrorCode::InsufficientBalance);
+        if vault_state.has_multisig() {
+            VaultMultisig::check_remaining_accounts(
+                &ctx.accounts.vault_state.key(),
+                &ctx.accounts.user.key(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        vault_state.consume_withdraw_limit(amount, now)?;
+        vault_state.deposited = vault_state.deposited.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    // The program owns vault_state, so lamports can be moved without a CPI
+    let rent_minimum = Rent::get()?.minimum_balance(VaultState::SPACE);
+    let remaining = ctx.accounts.vault_state.get_lamports().checked_sub(amount).ok_or(ErrorCode::InsufficientBalance)?;
+    require!(remaining >= rent_minimum, ErrorCode::InsufficientBalance);
+
+    ctx.accounts.vault_state.sub_lamports(amount)?;
+    ctx.accounts.user.add_lamports(amount)?;
+
+    let snapshot = {
+        let vault_balance = ctx.accounts.vault_state.get_lamports() - rent_minimum;
+        let mut vault_state = ctx.accounts.vault_state.load_vault_mut()?;
+        VaultSnapshot::take(&mut vault_state, AuditOp::Withdraw, amount, vault_balance)?
+    };
+
+    emit_cpi!(WithdrawEvent {
+        owner: ctx.accounts.user.key(),
+        mint: NATIVE_MINT,
+        amount,
+        gross_amount: amount,
+        fee: 0, // protocol fees are only charged on token vaults
+        snapshot,
+    });
+    Ok(())
+}
@@ -15,16 +15,154 @@ declare_id!("8mkgZQT7izpwtkxuy7ModN6NmeQCGJrQ2TvXqL8LpfjD");
 pub mod anchor {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::handle_initialize(ctx)
+    pub fn initialize(ctx: Context<Initialize>, unlock_ts: i64) -> Result<()> {
+        instructions::handle_initialize(ctx, unlock_ts)
+    }
+    pub fn set_mint_policy(ctx: Context<SetMintPolicy>, policy: MintPolicy) -> Result<()> {
+        instructions::handle_set_mint_policy(ctx, policy)
+    }
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::handle_set_paused(ctx, paused)
+    }
+    pub fn set_fees(ctx: Context<SetFees>, deposit_fee_bps: u16, withdraw_fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        instructions::handle_set_fees(ctx, deposit_fee_bps, withdraw_fee_bps, treasury)
+    }
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::handle_propose_admin(ctx, new_admin)
+    }
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::handle_accept_admin(ctx)
     }
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         instructions::handle_deposit(ctx, amount)
     }
+    pub fn deposit_for(ctx: Context<DepositFor>, owner: Pubkey, amount: u64) -> Result<()> {
+        instructions::handle_deposit_for(ctx, owner, amount)
+    }
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         instructions::handle_withdraw(ctx, amount)
     }
     pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
         instructions::handle_close_vault(ctx)
     }
+    pub fn sweep_and_close(ctx: Context<SweepAndClose>) -> Result<()> {
+        instructions::handle_sweep_and_close(ctx)
+    }
+    pub fn reconcile(ctx: Context<Reconcile>) -> Result<()> {
+        instructions::handle_reconcile(ctx)
+    }
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        instructions::handle_migrate_vault(ctx)
+    }
+    pub fn top_up_rent(ctx: Context<TopUpRent>) -> Result<()> {
+        instructions::handle_top_up_rent(ctx)
+    }
+    pub fn get_vault_info(ctx: Context<GetVaultInfo>) -> Result<VaultInfo> {
+        instructions::handle_get_vault_info(ctx)
+    }
+    pub fn batch_deposit<'info>(ctx: Context<'_, '_, 'info, 'info, BatchDeposit<'info>>, amounts: Vec<u64>) -> Result<()> {
+        instructions::handle_batch_deposit(ctx, amounts)
+    }
+    pub fn batch_withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, BatchWithdraw<'info>>, amounts: Vec<u64>) -> Result<()> {
+        instructions::handle_batch_withdraw(ctx, amounts)
+    }
+    pub fn payout<'info>(ctx: Context<'_, '_, 'info, 'info, Payout<'info>>, amounts: Vec<u64>) -> Result<()> {
+        instructions::handle_payout(ctx, amounts)
+    }
+    pub fn initialize_native(ctx: Context<InitializeNative>, unlock_ts: i64) -> Result<()> {
+        instructions::handle_initialize_native(ctx, unlock_ts)
+    }
+    pub fn deposit_native(ctx: Context<DepositNative>, amount: u64) -> Result<()> {
+        instructions::handle_deposit_native(ctx, amount)
+    }
+    pub fn withdraw_native(ctx: Context<WithdrawNative>, amount: u64) -> Result<()> {
+        instructions::handle_withdraw_native(ctx, amount)
+    }
+    pub fn close_native_vault(ctx: Context<CloseNativeVault>) -> Result<()> {
+        instructions::handle_close_native_vault(ctx)
+    }
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_ts: i64) -> Result<()> {
+        instructions::handle_extend_lock(ctx, unlock_ts)
+    }
+    pub fn set_withdraw_cooldown(ctx: Context<SetWithdrawCooldown>, cooldown: i64) -> Result<()> {
+        instructions::handle_set_withdraw_cooldown(ctx, cooldown)
+    }
+    pub fn set_withdraw_limit(ctx: Context<SetWithdrawLimit>, limit: u64, window: i64) -> Result<()> {
+        instructions::handle_set_withdraw_limit(ctx, limit, window)
+    }
+    pub fn request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()> {
+        instructions::handle_request_withdraw(ctx, amount)
+    }
+    pub fn claim_withdraw(ctx: Context<ClaimWithdraw>) -> Result<()> {
+        instructions::handle_claim_withdraw(ctx)
+    }
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        instructions::handle_cancel_withdraw(ctx)
+    }
+    pub fn enable_multisig(ctx: Context<EnableMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::handle_enable_multisig(ctx, signers, threshold)
+    }
+    pub fn enable_allowlist(ctx: Context<EnableAllowlist>, delay: i64) -> Result<()> {
+        instructions::handle_enable_allowlist(ctx, delay)
+    }
+    pub fn add_allowed_destination(ctx: Context<AddAllowedDestination>, destination: Pubkey) -> Result<()> {
+        instructions::handle_add_allowed_destination(ctx, destination)
+    }
+    pub fn remove_allowed_destination(ctx: Context<RemoveAllowedDestination>, destination: Pubkey) -> Result<()> {
+        instructions::handle_remove_allowed_destination(ctx, destination)
+    }
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        instructions::handle_set_guardian(ctx, guardian)
+    }
+    pub fn freeze_vault(ctx: Context<FreezeVault>) -> Result<()> {
+        instructions::handle_freeze_vault(ctx)
+    }
+    pub fn unfreeze_vault(ctx: Context<UnfreezeVault>) -> Result<()> {
+        instructions::handle_unfreeze_vault(ctx)
+    }
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        instructions::handle_propose_owner(ctx, new_owner)
+    }
+    pub fn cancel_owner_proposal(ctx: Context<CancelOwnerProposal>) -> Result<()> {
+        instructions::handle_cancel_owner_proposal(ctx)
+    }
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        instructions::handle_accept_owner(ctx)
+    }
+    pub fn create_receipt_mint(ctx: Context<CreateReceiptMint>) -> Result<()> {
+        instructions::handle_create_receipt_mint(ctx)
+    }
+    pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+        instructions::handle_redeem(ctx, amount)
+    }
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        instructions::handle_initialize_pool(ctx)
+    }
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        instructions::handle_open_position(ctx)
+    }
+    pub fn pool_deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
+        instructions::handle_pool_deposit(ctx, amount)
+    }
+    pub fn pool_withdraw(ctx: Context<PoolWithdraw>, shares: u64) -> Result<()> {
+        instructions::handle_pool_withdraw(ctx, shares)
+    }
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::handle_close_position(ctx)
+    }
+    pub fn initialize_basket(ctx: Context<InitializeBasket>) -> Result<()> {
+        instructions::handle_initialize_basket(ctx)
+    }
+    pub fn add_basket_mint(ctx: Context<AddBasketMint>) -> Result<()> {
+        instructions::handle_add_basket_mint(ctx)
+    }
+    pub fn basket_deposit(ctx: Context<BasketDeposit>, index: u8, amount: u64) -> Result<()> {
+        instructions::handle_basket_deposit(ctx, index, amount)
+    }
+    pub fn basket_withdraw(ctx: Context<BasketWithdraw>, index: u8, amount: u64) -> Result<()> {
+        instructions::handle_basket_withdraw(ctx, index, amount)
+    }
+    pub fn close_basket<'info>(ctx: Context<'_, '_, 'info, 'info, CloseBasket<'info>>) -> Result<()> {
+        instructions::handle_close_basket(ctx)
+    }
 }
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::MAX_BASKET_MINTS;
+
+// One owner-level account tracking balances for up to `MAX_BASKET_MINTS` mints.
+// Each mint's tokens sit in their own `["basket_account", basket, mint]` PDA.
+#[account(zero_copy)]
+pub struct BasketState {
+    pub user: Pubkey,
+    pub token_program: Pubkey, // every mint in the basket belongs to this token program
+    pub mints: [Pubkey; MAX_BASKET_MINTS],
+    pub balances: [u64; MAX_BASKET_MINTS],
+    pub bumps_token_account: [u8; MAX_BASKET_MINTS],
+    pub mint_count: u8,
+    pub bump: u8,
+    pub _padding: [u8; 6],
+    pub event_seq: u64, // sequence of the last `BasketSnapshot`
+}
+
+impl Default for BasketState {
+    fn default() -> Self {
+        Self {
+            user: Pubkey::default(),
+            token_program: Pubkey::default(),
+            mints: [Pubkey::default(); MAX_BASKET_MINTS],
+            balances: [0; MAX_BASKET_MINTS],
+            bumps_token_account: [0; MAX_BASKET_MINTS],
+            mint_count: 0,
+            bump: 0,
+            _padding: [0; 6],
+            event_seq: 0,
+        }
+    }
+}
+
+impl BasketState {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //user
+        32 + //token_program
+        32 * MAX_BASKET_MINTS + //mints
+        8 * MAX_BASKET_MINTS + //balances
+        MAX_BASKET_MINTS + //bumps_token_account
+        1 + //mint_count
+        1 + //bump
+        6 + //padding
+        8; //event_seq
+
+    pub fn mints(&self) -> &[Pubkey] {
+        &self.mints[..self.mint_count as usize]
+    }
+
+    // Index of `mint`, if it has been added to the basket
+    pub fn index_of(&self, mint: &Pubkey) -> Option<usize> {
+        self.mints().iter().position(|m| m == mint)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.balances.iter().all(|&balance| balance == 0)
+    }
+}
@@ -1,4 +1,87 @@
 use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::state::{AuditOp, BasketState, MintPolicy, PoolState, VaultState};
+use crate::MAX_BASKET_MINTS;
+
+// Vault state after the operation that emitted the event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct VaultSnapshot {
+    pub deposited: u64,
+    pub vault_balance: u64, // tokens held by the vault account, or lamports above rent for native vaults
+    pub slot: u64,
+    pub timestamp: i64,
+    pub sequence: u64, // per-vault, increases by one with every snapshot so gaps are detectable
+    pub audit_op: u8, // `AuditOp` chained by this operation
+    pub audit_amount: u64, // tokens or lamports the operation moved into or out of the vault
+    pub audit_count: u64,
+    pub audit_hash: [u8; 32], // H(previous audit_hash, audit_op, audit_amount, slot)
+}
+
+impl VaultSnapshot {
+    // Records `op` in the vault's audit chain, then captures the resulting state
+    pub fn take(vault_state: &mut VaultState, op: AuditOp, amount: u64, vault_balance: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+        vault_state.record_op(op, amount, clock.slot)?;
+        vault_state.event_seq = vault_state.event_seq.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self {
+            deposited: vault_state.deposited,
+            vault_balance,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            sequence: vault_state.event_seq,
+            audit_op: op as u8,
+            audit_amount: amount,
+            audit_count: vault_state.audit_count,
+            audit_hash: vault_state.audit_hash,
+        })
+    }
+}
+
+// Pool state after the operation that emitted the event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PoolSnapshot {
+    pub total_shares: u64,
+    pub pool_balance: u64, // tokens held by the pool account
+    pub slot: u64,
+    pub timestamp: i64,
+    pub sequence: u64, // per-pool, increases by one with every snapshot
+}
+
+impl PoolSnapshot {
+    pub fn take(pool_state: &mut PoolState, pool_balance: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+        pool_state.event_seq = pool_state.event_seq.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self {
+            total_shares: pool_state.total_shares,
+            pool_balance,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            sequence: pool_state.event_seq,
+        })
+    }
+}
+
+// Basket balances after the operation that emitted the event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BasketSnapshot {
+    pub balances: [u64; MAX_BASKET_MINTS],
+    pub slot: u64,
+    pub timestamp: i64,
+    pub sequence: u64, // per-basket, increases by one with every snapshot
+}
+
+impl BasketSnapshot {
+    pub fn take(basket: &mut BasketState) -> Result<Self> {
+        let clock = Clock::get()?;
+        basket.event_seq = basket.event_seq.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self {
+            balances: basket.balances,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            sequence: basket.event_seq,
+        })
+    }
+}
 
 #[event]
 pub struct InitializeEvent {
@@ -10,18 +93,339 @@ pub struct InitializeEvent {
 pub struct DepositEvent {
     pub owner: Pubkey,
     pub mint: Pubkey,
-    pub amount: u64,
+    pub amount: u64, // net: credited to the vault
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct DepositForEvent {
+    pub depositor: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // net: credited to the vault
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub snapshot: VaultSnapshot,
 }
 
 #[event]
 pub struct WithdrawEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // net: paid to the owner
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct LockExtendedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub unlock_ts: i64,
+}
+
+#[event]
+pub struct WithdrawCooldownSetEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub cooldown: i64,
+}
+
+#[event]
+pub struct WithdrawLimitSetEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub limit: u64,
+    pub window: i64,
+    pub effective_ts: i64,
+}
+
+#[event]
+pub struct WithdrawRequestedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub ready_ts: i64,
+}
+
+#[event]
+pub struct WithdrawCancelledEvent {
     pub owner: Pubkey,
     pub mint: Pubkey,
     pub amount: u64,
 }
 
+#[event]
+pub struct OwnerProposedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipTransferredEvent {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub snapshot: VaultSnapshot, // of the new owner's vault, which continues the audit chain
+}
+
+#[event]
+pub struct MultisigEnabledEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
 #[event]
 pub struct CloseEvent {
     pub owner: Pubkey,
     pub mint: Pubkey,
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct SweepAndCloseEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // net: paid to the owner
+    pub gross_amount: u64, // whole vault balance swept
+    pub fee: u64,
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct ConfigInitializedEvent {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct MintPolicySetEvent {
+    pub admin: Pubkey,
+    pub policy: MintPolicy,
+}
+
+#[event]
+pub struct ReconcileEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub surplus: u64,
+    pub deposited: u64, // after crediting the surplus
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct VaultMigratedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct RentToppedUpEvent {
+    pub payer: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // lamports added across vault_state and vault_account
+}
+
+#[event]
+pub struct ReceiptMintCreatedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub receipt_mint: Pubkey,
+}
+
+#[event]
+pub struct RedeemEvent {
+    pub owner: Pubkey,
+    pub holder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // net: paid to the holder
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct InitializePoolEvent {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PoolDepositEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub assets: u64,
+    pub shares: u64,
+    pub snapshot: PoolSnapshot,
+}
+
+#[event]
+pub struct PoolWithdrawEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub assets: u64,
+    pub shares: u64,
+    pub snapshot: PoolSnapshot,
+}
+
+#[event]
+pub struct InitializeBasketEvent {
+    pub basket: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct BasketMintAddedEvent {
+    pub basket: Pubkey,
+    pub mint: Pubkey,
+    pub index: u8,
+}
+
+#[event]
+pub struct BasketDepositEvent {
+    pub basket: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub snapshot: BasketSnapshot,
+}
+
+#[event]
+pub struct BasketWithdrawEvent {
+    pub basket: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub snapshot: BasketSnapshot,
+}
+
+#[event]
+pub struct CloseBasketEvent {
+    pub basket: Pubkey,
+    pub owner: Pubkey,
+    pub snapshot: BasketSnapshot,
+}
+
+#[event]
+pub struct PausedSetEvent {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct FeesSetEvent {
+    pub admin: Pubkey,
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    pub treasury: Pubkey,
+}
+
+// One vault's part of a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchEntry {
+    pub mint: Pubkey,
+    pub amount: u64, // credited on deposit, paid out on withdraw
+    pub snapshot: VaultSnapshot,
+}
+
+#[event]
+pub struct BatchDepositEvent {
+    pub owner: Pubkey,
+    pub deposits: Vec<BatchEntry>,
+}
+
+#[event]
+pub struct BatchWithdrawEvent {
+    pub owner: Pubkey,
+    pub withdrawals: Vec<BatchEntry>,
+}
+
+// One per recipient; the events of a payout share its snapshot, whose `audit_amount` is the gross
+#[event]
+pub struct PayoutEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64, // net: paid to all recipients
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub payments: Vec<PayoutEntry>, // in the order of `amounts`
+    pub snapshot: VaultSnapshot,
+}
+
+// One recipient's part of a payout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PayoutEntry {
+    pub recipient: Pubkey, // token account paid
+    pub amount: u64, // net: paid to the recipient
+    pub gross_amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct AllowlistEnabledEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub delay: i64,
+}
+
+#[event]
+pub struct DestinationAddedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub active_ts: i64,
+}
+
+#[event]
+pub struct DestinationRemovedEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct GuardianSetEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub guardian: Pubkey, // default when the guardian was removed
+}
+
+#[event]
+pub struct VaultFrozenEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub frozen_by: Pubkey, // the owner or the guardian
+    pub unfreeze_ts: i64,
+}
+
+#[event]
+pub struct VaultUnfrozenEvent {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub guardian_signed: bool, // false when the owner waited out the delay alone
 }
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, permanent_delegate::PermanentDelegate,
+        transfer_hook::TransferHook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::AccountState,
+};
+use anchor_spl::token_interface::Mint;
+use crate::error::ErrorCode;
+use crate::NATIVE_DECIMALS;
+
+// Rules a mint has to satisfy before a vault can be created for it; set by the admin
+// and stored in ProgramConfig
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintPolicy {
+    pub reject_freeze_authority: bool,
+    pub min_decimals: u8,
+    pub max_decimals: u8,
+    // Token-2022 extensions
+    pub reject_permanent_delegate: bool,
+    pub reject_non_transferable: bool,
+    pub reject_transfer_hook: bool,
+    pub reject_default_frozen: bool,
+}
+
+impl Default for MintPolicy {
+    // Accepts any mint, matching the behaviour before policies existed
+    fn default() -> Self {
+        Self {
+            reject_freeze_authority: false,
+            min_decimals: 0,
+            max_decimals: u8::MAX,
+            reject_permanent_delegate: false,
+            reject_non_transferable: false,
+            reject_transfer_hook: false,
+            reject_default_frozen: false,
+        }
+    }
+}
+
+impl MintPolicy {
+    pub fn check(&self, mint: &InterfaceAccount<Mint>) -> Result<()> {
+        require!(self.min_decimals <= self.max_decimals, ErrorCode::InvalidArgument);
+        require!(
+            !(self.reject_freeze_authority && mint.freeze_authority.is_some()),
+            ErrorCode::MintHasFreezeAuthority
+        );
+        require!(
+            mint.decimals >= self.min_decimals && mint.decimals <= self.max_decimals,
+            ErrorCode::MintDecimalsOutOfRange
+        );
+
+        // Legacy SPL-Token mints carry no extensions
+        let mint_info = mint.to_account_info();
+        if *mint_info.owner != spl_token_2022::ID {
+            return Ok(());
+        }
+
+        let data = mint_info.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        for extension in state.get_extension_types()? {
+            match extension {
+                ExtensionType::PermanentDelegate if self.reject_permanent_delegate => {
+                    let delegate = state.get_extension::<PermanentDelegate>()?.delegate;
+                    require!(
+                        Option::<Pubkey>::from(delegate).is_none(),
+                        ErrorCode::MintHasPermanentDelegate
+                    );
+                }
+                ExtensionType::NonTransferable if self.reject_non_transferable => {
+                    return err!(ErrorCode::MintNonTransferable);
+                }
+                ExtensionType::TransferHook if self.reject_transfer_hook => {
+                    let program_id = state.get_extension::<TransferHook>()?.program_id;
+                    require!(
+                        Option::<Pubkey>::from(program_id).is_none(),
+                        ErrorCode::MintHasTransferHook
+                    );
+                }
+                ExtensionType::DefaultAccountState if self.reject_default_frozen => {
+                    let default_state = state.get_extension::<DefaultAccountState>()?.state;
+                    require!(
+                        default_state != AccountState::Frozen as u8,
+                        ErrorCode::MintDefaultFrozen
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Native SOL has no authorities or extensions, only its decimals can fall outside the policy
+    pub fn check_native(&self) -> Result<()> {
+        require!(self.min_decimals <= self.max_decimals, ErrorCode::InvalidArgument);
+        require!(
+            NATIVE_DECIMALS >= self.min_decimals && NATIVE_DECIMALS <= self.max_decimals,
+            ErrorCode::MintDecimalsOutOfRange
+        );
+        Ok(())
+    }
+}
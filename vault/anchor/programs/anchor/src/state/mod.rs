@@ -1,4 +1,18 @@
+pub mod basket_state;
 pub mod events;
+pub mod mint_policy;
+pub mod pool_state;
+pub mod program_config;
+pub mod vault_info;
+pub mod vault_multisig;
 pub mod vault_state;
+pub mod withdraw_allowlist;
 
-pub use vault_state::*;
\ No newline at end of file
+pub use basket_state::*;
+pub use mint_policy::*;
+pub use pool_state::*;
+pub use program_config::*;
+pub use vault_info::*;
+pub use vault_multisig::*;
+pub use vault_state::*;
+pub use withdraw_allowlist::*;
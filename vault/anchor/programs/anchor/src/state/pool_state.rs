@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+// Shared vault for one mint; depositors own it pro-rata through `PoolPosition` shares
+#[account(zero_copy)]
+#[derive(Default)]
+pub struct PoolState {
+    pub mint: Pubkey,
+    pub total_shares: u64,
+    pub bump: u8,
+    pub bump_token_account: u8,
+    pub _padding: [u8; 6],
+    pub event_seq: u64, // sequence of the last `PoolSnapshot`
+}
+
+impl PoolState {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //mint
+        8 + //total_shares
+        1 + //bump
+        1 + //bump_token_account
+        6 + //padding
+        8; //event_seq
+
+    // A virtual share and asset keep the first depositor from inflating the share
+    // price enough to round later deposits down to zero.
+    pub const VIRTUAL_SHARES: u128 = 1;
+    pub const VIRTUAL_ASSETS: u128 = 1;
+
+    // Shares minted for `assets`, rounded down so the pool keeps the remainder
+    pub fn convert_to_shares(&self, assets: u64, total_assets: u64) -> Result<u64> {
+        let shares = (assets as u128)
+            .checked_mul(self.total_shares as u128 + Self::VIRTUAL_SHARES)
+            .ok_or(ErrorCode::MathOverflow)?
+            / (total_assets as u128 + Self::VIRTUAL_ASSETS);
+        u64::try_from(shares).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    // Assets paid out for `shares`, rounded down so the pool keeps the remainder
+    pub fn convert_to_assets(&self, shares: u64, total_assets: u64) -> Result<u64> {
+        let assets = (shares as u128)
+            .checked_mul(total_assets as u128 + Self::VIRTUAL_ASSETS)
+            .ok_or(ErrorCode::MathOverflow)?
+            / (self.total_shares as u128 + Self::VIRTUAL_SHARES);
+        u64::try_from(assets).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+}
+
+#[account(zero_copy)]
+#[derive(Default)]
+pub struct PoolPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl PoolPosition {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //pool
+        32 + //owner
+        8 + //shares
+        1 + //bump
+        7; // padding
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::MintPolicy;
+use crate::BPS_DENOMINATOR;
+
+// Program-wide settings, a single PDA at `["config"]`
+#[account(zero_copy)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey, // proposed by `admin`, takes over on accept_admin; default when none
+    pub paused: u8, // 1 while fund-moving instructions are halted
+    pub bump: u8,
+    pub deposit_fee_bps: u16, // at most MAX_FEE_BPS
+    pub withdraw_fee_bps: u16, // at most MAX_FEE_BPS
+    pub _padding: [u8; 2],
+    pub treasury: Pubkey, // owner of the per-mint token accounts that collect fees
+    // MintPolicy applied by initialize, initialize_native, initialize_pool and add_basket_mint,
+    // flags stored as 0/1
+    pub reject_freeze_authority: u8,
+    pub min_decimals: u8,
+    pub max_decimals: u8,
+    pub reject_permanent_delegate: u8,
+    pub reject_non_transferable: u8,
+    pub reject_transfer_hook: u8,
+    pub reject_default_frozen: u8,
+    pub _policy_padding: [u8; 1],
+}
+
+impl Default for ProgramConfig {
+    // Accepts any mint until the admin sets a policy
+    fn default() -> Self {
+        let mut config: Self = bytemuck::Zeroable::zeroed();
+        config.set_mint_policy(&MintPolicy::default());
+        config
+    }
+}
+
+impl ProgramConfig {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //admin
+        32 + //pending_admin
+        1 + //paused
+        1 + //bump
+        2 + //deposit_fee_bps
+        2 + //withdraw_fee_bps
+        2 + // padding
+        32 + //treasury
+        7 + //mint policy
+        1; // padding
+
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    pub fn mint_policy(&self) -> MintPolicy {
+        MintPolicy {
+            reject_freeze_authority: self.reject_freeze_authority != 0,
+            min_decimals: self.min_decimals,
+            max_decimals: self.max_decimals,
+            reject_permanent_delegate: self.reject_permanent_delegate != 0,
+            reject_non_transferable: self.reject_non_transferable != 0,
+            reject_transfer_hook: self.reject_transfer_hook != 0,
+            reject_default_frozen: self.reject_default_frozen != 0,
+        }
+    }
+
+    pub fn set_mint_policy(&mut self, policy: &MintPolicy) {
+        self.reject_freeze_authority = policy.reject_freeze_authority as u8;
+        self.min_decimals = policy.min_decimals;
+        self.max_decimals = policy.max_decimals;
+        self.reject_permanent_delegate = policy.reject_permanent_delegate as u8;
+        self.reject_non_transferable = policy.reject_non_transferable as u8;
+        self.reject_transfer_hook = policy.reject_transfer_hook as u8;
+        self.reject_default_frozen = policy.reject_default_frozen as u8;
+    }
+
+    pub fn deposit_fee(&self, amount: u64) -> u64 {
+        Self::fee(amount, self.deposit_fee_bps)
+    }
+
+    pub fn withdraw_fee(&self, amount: u64) -> u64 {
+        Self::fee(amount, self.withdraw_fee_bps)
+    }
+
+    // Rounded down; `bps` never exceeds BPS_DENOMINATOR so the result fits in u64
+    fn fee(amount: u64, bps: u16) -> u64 {
+        (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+// Decoded view of a vault, returned by get_vault_info
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultInfo {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub deposited: u64,
+    pub vault_balance: u64, // tokens held by the vault account, or lamports above rent for native vaults
+    pub decimals: u8,
+    pub pending_withdraw: u64, // reserved by an open withdraw request
+    pub locked: bool,
+    pub unlock_ts: i64,
+    pub withdraw_limit: u64, // 0 means no limit; a scheduled raise shows once its delay has passed
+    pub withdraw_window: i64,
+    pub withdraw_limit_remaining: u64, // u64::MAX when there is no limit
+    pub pending_withdraw_limit: u64,
+    pub pending_limit_ts: i64, // 0 if no limit change is scheduled
+    pub frozen: bool,
+    pub unfreeze_ts: i64, // when the owner alone may unfreeze; 0 while not frozen
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::error::ErrorCode;
+use crate::{MAX_MULTISIG_SIGNERS, MULTISIG_SEED};
+
+// M-of-N signer set guarding withdraw and close for one vault
+#[account(zero_copy)]
+pub struct VaultMultisig {
+    pub vault: Pubkey,
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    pub signer_count: u8,
+    pub threshold: u8,
+    pub bump: u8,
+    pub _padding: [u8; 5],
+}
+
+impl Default for VaultMultisig {
+    fn default() -> Self {
+        Self {
+            vault: Pubkey::default(),
+            signers: [Pubkey::default(); MAX_MULTISIG_SIGNERS],
+            signer_count: 0,
+            threshold: 0,
+            bump: 0,
+            _padding: [0; 5],
+        }
+    }
+}
+
+impl VaultMultisig {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //vault
+        32 * MAX_MULTISIG_SIGNERS + //signers
+        1 + //signer_count
+        1 + //threshold
+        1 + //bump
+        5; // padding
+
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
+
+    // Loads the multisig for `vault` from the first remaining account and requires
+    // `threshold` distinct configured signers among the signing `user` and the rest of the accounts.
+    pub fn check_remaining_accounts(vault: &Pubkey, user: &Pubkey, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        let (multisig_info, signer_infos) = remaining_accounts
+            .split_first()
+            .ok_or(ErrorCode::MultisigThresholdNotMet)?;
+
+        let (expected, _) = Pubkey::find_program_address(&[MULTISIG_SEED, vault.as_ref()], &crate::ID);
+        require_keys_eq!(multisig_info.key(), expected, ErrorCode::InvalidMultisig);
+        require_keys_eq!(*multisig_info.owner, crate::ID, ErrorCode::InvalidMultisig);
+
+        let multisig: VaultMultisig = {
+            let data = multisig_info.try_borrow_data()?;
+            require!(
+                data.len() >= Self::SPACE && data[..8] == *Self::DISCRIMINATOR,
+                ErrorCode::InvalidMultisig
+            );
+            bytemuck::pod_read_unaligned(&data[8..Self::SPACE])
+        };
+
+        let signed_keys = std::iter::once(user)
+            .chain(signer_infos.iter().filter(|info| info.is_signer).map(|info| info.key));
+        let mut approved: Vec<Pubkey> = Vec::with_capacity(multisig.signer_count as usize);
+        for key in signed_keys {
+            if multisig.signers().contains(key) && !approved.contains(key) {
+                approved.push(*key);
+            }
+        }
+        require!(approved.len() >= multisig.threshold as usize, ErrorCode::MultisigThresholdNotMet);
+        Ok(())
+    }
+}
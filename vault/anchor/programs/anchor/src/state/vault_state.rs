@@ -1,14 +1,86 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use std::cell::{Ref, RefMut};
+use crate::error::ErrorCode;
+use crate::VAULT_STATE_VERSION;
 
 #[account(zero_copy)]
-#[derive(Default)]
 pub struct VaultState {
     pub user: Pubkey,
     pub mint: Pubkey,
     pub deposited: u64,
     pub bump: u8,
     pub bump_token_account: u8,
-    pub _padding: [u8; 6], // Padding to ensure the size is 64 bytes
+    pub bump_receipt_mint: u8,
+    pub receipt_mint_enabled: u8, // 1 once the receipt mint has been created
+    pub multisig_enabled: u8, // 1 once withdraw and close need the `VaultMultisig` threshold
+    pub version: u8, // layout version, VAULT_STATE_VERSION once initialized or migrated
+    pub allowlist_enabled: u8, // 1 once withdrawals may only go to `WithdrawAllowlist` destinations
+    pub frozen: u8, // 1 while freeze_vault has stopped withdrawals and closes
+    pub unlock_ts: i64, // withdraw and close are refused before this unix timestamp
+    pub withdraw_cooldown: i64, // seconds between request_withdraw and claim_withdraw; 0 allows direct withdraw
+    pub pending_withdraw: u64, // amount reserved by an open withdraw request
+    pub withdraw_limit: u64, // most that may leave per `withdraw_window`; 0 means no limit
+    pub withdraw_window: i64, // length of the rolling limit window in seconds
+    pub window_start: i64, // start of the current limit window
+    pub window_used: u64, // amount withdrawn in the current window
+    pub pending_withdraw_limit: u64, // raised limit waiting for `pending_limit_ts`
+    pub pending_withdraw_window: i64,
+    pub pending_limit_ts: i64, // when the pending limit takes over; 0 if none is scheduled
+    pub rent_payer: Pubkey, // refunded the rent on close; default for vaults that predate it
+    pub event_seq: u64, // bumped by every event that carries a `VaultSnapshot`
+    pub audit_count: u64, // operations folded into `audit_hash`
+    pub audit_hash: [u8; 32], // H(prev, op, amount, slot) over every deposit, withdraw and close
+    pub guardian: Pubkey, // may freeze the vault and co-sign an early unfreeze; default if none
+    pub unfreeze_ts: i64, // when the owner alone may unfreeze; 0 while not frozen
+    pub _reserved: [u8; 40], // room for new fields without another realloc
+}
+
+// Operations recorded in the audit chain, hashed as their `u8` value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AuditOp {
+    Deposit = 1,
+    Withdraw = 2,
+    Close = 3,
+    Reconcile = 4, // surplus credited to `deposited`
+    Transfer = 5, // balance carried into the new owner's vault by accept_owner
+}
+
+// Arrays over 32 elements don't implement Default
+impl Default for VaultState {
+    fn default() -> Self {
+        Self {
+            user: Pubkey::default(),
+            mint: Pubkey::default(),
+            deposited: 0,
+            bump: 0,
+            bump_token_account: 0,
+            bump_receipt_mint: 0,
+            receipt_mint_enabled: 0,
+            multisig_enabled: 0,
+            version: VAULT_STATE_VERSION,
+            allowlist_enabled: 0,
+            frozen: 0,
+            unlock_ts: 0,
+            withdraw_cooldown: 0,
+            pending_withdraw: 0,
+            withdraw_limit: 0,
+            withdraw_window: 0,
+            window_start: 0,
+            window_used: 0,
+            pending_withdraw_limit: 0,
+            pending_withdraw_window: 0,
+            pending_limit_ts: 0,
+            rent_payer: Pubkey::default(),
+            event_seq: 0,
+            audit_count: 0,
+            audit_hash: [0; 32],
+            guardian: Pubkey::default(),
+            unfreeze_ts: 0,
+            _reserved: [0; 40],
+        }
+    }
 }
 
 
@@ -19,5 +91,201 @@ impl VaultState {
         8 + //deposited
         1 + //bump
         1 + //bumpt_token_account
-        6; // padding
-}
\ No newline at end of file
+        1 + //bump_receipt_mint
+        1 + //receipt_mint_enabled
+        1 + //multisig_enabled
+        1 + //version
+        1 + //allowlist_enabled
+        1 + //frozen
+        8 + //unlock_ts
+        8 + //withdraw_cooldown
+        8 + //pending_withdraw
+        8 + //withdraw_limit
+        8 + //withdraw_window
+        8 + //window_start
+        8 + //window_used
+        8 + //pending_withdraw_limit
+        8 + //pending_withdraw_window
+        8 + //pending_limit_ts
+        32 + //rent_payer
+        8 + //event_seq
+        8 + //audit_count
+        32 + //audit_hash
+        32 + //guardian
+        8 + //unfreeze_ts
+        40; //reserved
+
+    // Size of the baseline accounts created before the layout was versioned (version 0):
+    // user, mint, deposited, bump, bump_token_account and 6 bytes of padding
+    pub const LEGACY_SPACE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 6;
+
+    // Byte offset of `version` in the account data, readable whatever the account's size
+    pub const VERSION_OFFSET: usize = 8 + std::mem::offset_of!(VaultState, version);
+
+    // `AccountLoader::load` slices the data at the current struct size, so accounts in any other
+    // layout are turned away before it runs
+    pub fn check_version(info: &AccountInfo) -> Result<()> {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= Self::SPACE && data[Self::VERSION_OFFSET] == VAULT_STATE_VERSION,
+            ErrorCode::UnsupportedVaultVersion
+        );
+        Ok(())
+    }
+
+    pub fn has_receipt_mint(&self) -> bool {
+        self.receipt_mint_enabled != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.mint == crate::NATIVE_MINT
+    }
+
+    pub fn has_multisig(&self) -> bool {
+        self.multisig_enabled != 0
+    }
+
+    pub fn has_allowlist(&self) -> bool {
+        self.allowlist_enabled != 0
+    }
+
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.unlock_ts
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen != 0
+    }
+
+    pub fn has_guardian(&self) -> bool {
+        self.guardian != Pubkey::default()
+    }
+
+    // Who gets the rent back when the vault is closed
+    pub fn rent_refund(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.user
+        } else {
+            self.rent_payer
+        }
+    }
+
+    // Balance not reserved by a pending withdraw request
+    pub fn available(&self) -> u64 {
+        self.deposited.saturating_sub(self.pending_withdraw)
+    }
+
+    // Anyone can add tokens to the vault account, but `deposited` must never exceed what it holds
+    pub fn check_backing(&self, vault_balance: u64) -> Result<()> {
+        require!(self.deposited <= vault_balance, ErrorCode::VaultUnderfunded);
+        Ok(())
+    }
+
+    // Chains an operation onto `audit_hash`; the chain starts from 32 zero bytes
+    pub fn record_op(&mut self, op: AuditOp, amount: u64, slot: u64) -> Result<()> {
+        self.audit_hash = hashv(&[&self.audit_hash, &[op as u8], &amount.to_le_bytes(), &slot.to_le_bytes()]).to_bytes();
+        self.audit_count = self.audit_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Swaps in a scheduled limit once its delay has passed
+    pub fn apply_pending_limit(&mut self, now: i64) {
+        if self.pending_limit_ts != 0 && now >= self.pending_limit_ts {
+            self.withdraw_limit = self.pending_withdraw_limit;
+            self.withdraw_window = self.pending_withdraw_window;
+            self.pending_withdraw_limit = 0;
+            self.pending_withdraw_window = 0;
+            self.pending_limit_ts = 0;
+        }
+    }
+
+    // A change is tighter if it allows no more per window over an equal or longer window
+    pub fn is_tighter_limit(&self, limit: u64, window: i64) -> bool {
+        limit != 0 && (self.withdraw_limit == 0 || (limit <= self.withdraw_limit && window >= self.withdraw_window))
+    }
+
+    // How much the withdraw limit still lets out at `now`; u64::MAX when there is no limit
+    pub fn withdraw_limit_remaining(&self, now: i64) -> u64 {
+        let mut state = *self;
+        state.apply_pending_limit(now);
+        if state.withdraw_limit == 0 {
+            return u64::MAX;
+        }
+        if now >= state.window_start.saturating_add(state.withdraw_window) {
+            return state.withdraw_limit;
+        }
+        state.withdraw_limit.saturating_sub(state.window_used)
+    }
+
+    // Counts `amount` against the rolling withdraw limit, starting a new window when the last one ran out
+    pub fn consume_withdraw_limit(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.apply_pending_limit(now);
+        if self.withdraw_limit == 0 {
+            return Ok(());
+        }
+        if now >= self.window_start.saturating_add(self.withdraw_window) {
+            self.window_start = now;
+            self.window_used = 0;
+        }
+        let used = self.window_used.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(used <= self.withdraw_limit, ErrorCode::WithdrawLimitExceeded);
+        self.window_used = used;
+        Ok(())
+    }
+}
+
+// Version-checked loads; handlers use these instead of `load`/`load_mut` on `vault_state`
+pub trait VaultStateLoader {
+    fn load_vault(&self) -> Result<Ref<'_, VaultState>>;
+    fn load_vault_mut(&self) -> Result<RefMut<'_, VaultState>>;
+}
+
+impl<'info> VaultStateLoader for AccountLoader<'info, VaultState> {
+    fn load_vault(&self) -> Result<Ref<'_, VaultState>> {
+        VaultState::check_version(self.as_ref())?;
+        self.load()
+    }
+
+    fn load_vault_mut(&self) -> Result<RefMut<'_, VaultState>> {
+        VaultState::check_version(self.as_ref())?;
+        self.load_mut()
+    }
+}
+
+// Pending two-step withdrawal, one per vault
+#[account(zero_copy)]
+#[derive(Default)]
+pub struct WithdrawRequest {
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub ready_ts: i64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl WithdrawRequest {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //vault
+        8 + //amount
+        8 + //ready_ts
+        1 + //bump
+        7; // padding
+}
+
+// Pending ownership transfer, one per vault; the new owner completes it with accept_owner
+#[account(zero_copy)]
+#[derive(Default)]
+pub struct OwnerProposal {
+    pub vault: Pubkey,
+    pub new_owner: Pubkey,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl OwnerProposal {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //vault
+        32 + //new_owner
+        1 + //bump
+        7; // padding
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::state::VaultState;
+use crate::MAX_ALLOWLIST_DESTINATIONS;
+
+// Wallets a vault may withdraw to once its allowlist is enabled, one per vault
+#[account(zero_copy)]
+pub struct WithdrawAllowlist {
+    pub vault: Pubkey,
+    pub delay: i64, // seconds before an added destination can receive withdrawals
+    pub destinations: [Pubkey; MAX_ALLOWLIST_DESTINATIONS],
+    pub active_ts: [i64; MAX_ALLOWLIST_DESTINATIONS], // when each destination becomes usable
+    pub count: u8,
+    pub bump: u8,
+    pub _padding: [u8; 6],
+}
+
+impl Default for WithdrawAllowlist {
+    fn default() -> Self {
+        Self {
+            vault: Pubkey::default(),
+            delay: 0,
+            destinations: [Pubkey::default(); MAX_ALLOWLIST_DESTINATIONS],
+            active_ts: [0; MAX_ALLOWLIST_DESTINATIONS],
+            count: 0,
+            bump: 0,
+            _padding: [0; 6],
+        }
+    }
+}
+
+impl WithdrawAllowlist {
+    pub const SPACE: usize = 8 + //discriminator
+        32 + //vault
+        8 + //delay
+        32 * MAX_ALLOWLIST_DESTINATIONS + //destinations
+        8 * MAX_ALLOWLIST_DESTINATIONS + //active_ts
+        1 + //count
+        1 + //bump
+        6; // padding
+
+    pub fn destinations(&self) -> &[Pubkey] {
+        &self.destinations[..self.count as usize]
+    }
+
+    pub fn position(&self, destination: &Pubkey) -> Option<usize> {
+        self.destinations().iter().position(|key| key == destination)
+    }
+
+    // Whether withdrawals to token accounts owned by `destination` are allowed at `now`
+    pub fn allows(&self, destination: &Pubkey, now: i64) -> bool {
+        self.position(destination).is_some_and(|index| now >= self.active_ts[index])
+    }
+
+    pub fn add(&mut self, destination: Pubkey, now: i64) -> Result<i64> {
+        require!(self.position(&destination).is_none(), ErrorCode::InvalidArgument);
+        let index = self.count as usize;
+        require!(index < MAX_ALLOWLIST_DESTINATIONS, ErrorCode::AllowlistFull);
+        let active_ts = now.checked_add(self.delay).ok_or(ErrorCode::MathOverflow)?;
+        self.destinations[index] = destination;
+        self.active_ts[index] = active_ts;
+        self.count += 1;
+        Ok(active_ts)
+    }
+
+    // Order is not kept: the last entry takes the removed one's slot
+    pub fn remove(&mut self, destination: &Pubkey) -> Result<()> {
+        let index = self.position(destination).ok_or(ErrorCode::InvalidArgument)?;
+        let last = self.count as usize - 1;
+        self.destinations[index] = self.destinations[last];
+        self.active_ts[index] = self.active_ts[last];
+        self.destinations[last] = Pubkey::default();
+        self.active_ts[last] = 0;
+        self.count -= 1;
+        Ok(())
+    }
+
+    // Where an owner-initiated withdrawal may go: the owner's own token accounts until the
+    // vault enables an allowlist, and only its active destinations afterwards
+    pub fn check_destination(
+        vault_state: &VaultState,
+        allowlist: &Option<AccountLoader<WithdrawAllowlist>>,
+        destination_owner: &Pubkey,
+        now: i64,
+    ) -> Result<()> {
+        if !vault_state.has_allowlist() {
+            require_keys_eq!(*destination_owner, vault_state.user, anchor_lang::error::ErrorCode::ConstraintTokenOwner);
+            return Ok(());
+        }
+        let allowlist = allowlist.as_ref().ok_or(ErrorCode::MissingAllowlistAccount)?;
+        require!(allowlist.load()?.allows(destination_owner, now), ErrorCode::DestinationNotAllowed);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use anchor::VaultState;
+use anchor::{ProgramConfig, VaultState};
 use anchor_spl::associated_token::spl_associated_token_account;
 use mollusk_svm::{program, result::Check, Mollusk};
 use mollusk_svm_programs_token::token::keyed_account as keyed_account_for_token_program;
@@ -14,6 +14,7 @@ use spl_token::state::{Account as TokenAccount, AccountState, Mint};
 use anchor_lang::InstructionData;
 
 const PROGRAM_ID: Pubkey = Pubkey::from_str_const("8mkgZQT7izpwtkxuy7ModN6NmeQCGJrQ2TvXqL8LpfjD");
+const CONFIG_SEED: &[u8] = b"config";
 const VAULT_SEED: &[u8] = b"vault";
 const VAULT_ACCOUNT_SEED: &[u8] = b"vault_account";
 
@@ -67,6 +68,24 @@ fn main() {
         &[VAULT_ACCOUNT_SEED, vault_state_pda.as_ref()],
         &PROGRAM_ID,
     );
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[CONFIG_SEED], &PROGRAM_ID);
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID);
+
+    // Unpaused config with the default mint policy, required by initialize,
+    // deposit, withdraw and close
+    let mut config_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(ProgramConfig::SPACE),
+        ProgramConfig::SPACE,
+        &PROGRAM_ID,
+    );
+    let config_data = config_account.data_as_mut_slice();
+    let config_discriminator: [u8; 8] = anchor_lang::solana_program::hash::hash(b"account:ProgramConfig").to_bytes()[..8].try_into().unwrap();
+    config_data[..8].copy_from_slice(&config_discriminator);
+    config_data[8..].copy_from_slice(bytemuck::bytes_of(&ProgramConfig {
+        admin: user,
+        bump: config_bump,
+        ..Default::default()
+    }));
 
     // --- Benchmark 1: Initialize ---
     let user_account = Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program);
@@ -84,16 +103,20 @@ fn main() {
 
     let initialize_accounts = vec![
         AccountMeta::new(user, true),
+        AccountMeta::new(user, true), // payer
         AccountMeta::new(vault_state_pda, false),
         AccountMeta::new(vault_account_pda, false),
         AccountMeta::new_readonly(token_mint, false),
         AccountMeta::new_readonly(system_program, false),
         AccountMeta::new_readonly(token_program, false),
         AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // program
     ];
     let initialize_instruction = Instruction::new_with_bytes(
         PROGRAM_ID,
-        &(anchor::instruction::Initialize {}).data(),
+        &(anchor::instruction::Initialize { unlock_ts: 0 }).data(),
         initialize_accounts,
     );
     let initialize_tx_accounts = vec![
@@ -104,16 +127,18 @@ fn main() {
         (system_program, system_account.clone()),
         (token_program, token_program_account.clone()),
         (rent::id(), rent_account.clone().into()),
+        (config_pda, config_account.clone()),
+        (event_authority, Account::default()),
     ];
 
     // --- Benchmark 2: Deposit ---
     let initialized_vault_state = anchor::state::VaultState {
         user,
         mint: token_mint,
-        bump: state_bump as u8,
-        bump_token_account: vault_account_bump as u8,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
         deposited: 8_000_000,
-        _padding: [0; 6],
+        ..Default::default()
     };
     
     // Get data allocated in state_account
@@ -139,6 +164,12 @@ fn main() {
         AccountMeta::new(vault_account_pda, false),
         AccountMeta::new_readonly(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // receipt_mint (none)
+        AccountMeta::new_readonly(PROGRAM_ID, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // treasury_account (none)
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // program
     ];
     println!("Deposit accounts: {:?}", deposit_accounts);
 
@@ -153,7 +184,9 @@ fn main() {
         (vault_state_pda, vault_state_account.clone()),
         (vault_account_pda, initialized_vault_token.clone().into()),
         (token_mint, mint_account.clone().into()),
-        (token_program, token_program_account.clone().into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority, Account::default()),
     ];
 
     // --- Benchmark 3: Withdraw ---
@@ -164,24 +197,33 @@ fn main() {
         AccountMeta::new(user_token_account, false),
         AccountMeta::new(vault_state_pda, false),
         AccountMeta::new(vault_account_pda, false),
-        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // receipt_mint (none)
+        AccountMeta::new_readonly(PROGRAM_ID, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // treasury_account (none)
+        AccountMeta::new_readonly(PROGRAM_ID, false), // allowlist (none)
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // program
     ];
-    let withdraw_instruction = Instruction::new_with_bytes(
+    let _withdraw_instruction = Instruction::new_with_bytes(
         PROGRAM_ID,
         &(anchor::instruction::Withdraw { amount: 250_000 }).data(),
         withdraw_accounts,
     );
-    let withdraw_tx_accounts = vec![
+    let _withdraw_tx_accounts = [
         (user, user_account.clone()),
         (user_token_account, user_token_account_after_deposit.clone().into()),
         (vault_state_pda, vault_state_account.clone()),
         (vault_account_pda, vault_with_funds.clone().into()),
         (token_mint, mint_account.clone().into()),
-        (token_program, token_program_account.clone().into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority, Account::default()),
     ];
 
-    // --- Benchmark 4: CloseVault ---
+    // --- Benchmark 4: SweepAndClose ---
     let vault_with_remaining = get_token_account(&vault_state_pda, &token_mint, 250_000);
     let user_token_account_after_withdraw = get_token_account(&user, &token_mint, 750_000);
     let close_accounts = vec![
@@ -192,24 +234,32 @@ fn main() {
         AccountMeta::new_readonly(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
         AccountMeta::new_readonly(system_program, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // treasury_account (none)
+        AccountMeta::new_readonly(PROGRAM_ID, false), // allowlist (none)
+        AccountMeta::new(user, false), // rent_payer
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false), // program
     ];
-    let close_instruction = Instruction::new_with_bytes(
+    let _close_instruction = Instruction::new_with_bytes(
         PROGRAM_ID,
-        &anchor::instruction::CloseVault {}.data(),
+        &anchor::instruction::SweepAndClose {}.data(),
         close_accounts,
     );
-    let close_tx_accounts = vec![
+    let _close_tx_accounts = [
         (user, user_account),
         (user_token_account, user_token_account_after_withdraw.clone().into()),
-        (vault_state_pda, vault_state_account.clone().into()),
+        (vault_state_pda, vault_state_account.clone()),
         (vault_account_pda, vault_with_remaining.clone().into()),
         (token_mint, mint_account.clone().into()),
-        (token_program, token_program_account.clone().into()),
-        (system_program, system_account.clone().into()),
+        (token_program, token_program_account.clone()),
+        (system_program, system_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority, Account::default()),
     ];
 
     
-    let checks = &vec![Check::success()];
+    let checks = &[Check::success()];
 
     mollusk.process_and_validate_instruction(&initialize_instruction, &initialize_tx_accounts, checks);
     mollusk.process_and_validate_instruction(&deposit_instruction, &deposit_tx_accounts, checks);
@@ -1,15 +1,29 @@
-use anchor::VaultState;
-use anchor_lang::InstructionData;
+use anchor::{AuditOp, BasketState, MintPolicy, OwnerProposal, PoolPosition, PoolState, ProgramConfig, VaultInfo, VaultMultisig, VaultState, WithdrawAllowlist, WithdrawRequest};
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData};
+use anchor_lang::solana_program::hash::hashv;
 use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions,
+        StateWithExtensionsMut,
+    },
+};
 #[cfg(test)]
 use mollusk_svm::{Mollusk, program, result::Check};
 use mollusk_svm_programs_token::token::{
     keyed_account as keyed_account_for_token_program,
 };
+use mollusk_svm_programs_token::token2022::{
+    keyed_account as keyed_account_for_token_2022_program,
+};
 use solana_sdk::{
     account::{Account, AccountSharedData, WritableAccount, ReadableAccount},
     instruction::{AccountMeta, Instruction},
     native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
     program_option::COption, 
     program_pack::Pack,
     pubkey::Pubkey,
@@ -50,6 +64,106 @@ pub fn get_token_account(owner: &Pubkey, mint: &Pubkey, amount: u64) -> AccountS
     account
 }
 
+// Token-2022 mint, optionally carrying a transfer fee of `fee_basis_points`
+fn get_mint_account_2022(mint_authority: &Pubkey, supply: u64, fee_basis_points: Option<u16>) -> AccountSharedData {
+    let extensions: &[ExtensionType] = if fee_basis_points.is_some() { &[ExtensionType::TransferFeeConfig] } else { &[] };
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions).unwrap();
+    let mut account = AccountSharedData::new(0, space, &spl_token_2022::id());
+
+    let data = account.data_as_mut_slice();
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(data).unwrap();
+    if let Some(basis_points) = fee_basis_points {
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        };
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+    }
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    account
+}
+
+// Token-2022 account, with room for withheld fees when the mint charges transfer fees
+fn get_token_account_2022(owner: &Pubkey, mint: &Pubkey, amount: u64, transfer_fee: bool) -> AccountSharedData {
+    let extensions: &[ExtensionType] = if transfer_fee { &[ExtensionType::TransferFeeAmount] } else { &[] };
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(extensions).unwrap();
+    let mut account = AccountSharedData::new(0, space, &spl_token_2022::id());
+
+    let data = account.data_as_mut_slice();
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(data).unwrap();
+    if transfer_fee {
+        state.init_extension::<TransferFeeAmount>(true).unwrap();
+    }
+    state.base = spl_token_2022::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token_2022::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    account
+}
+
+// Allocates a program owned vault state account holding `vault_state`
+fn get_vault_state_account(mollusk: &Mollusk, program_id: &Pubkey, vault_state: VaultState) -> Account {
+    let mut account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(VaultState::SPACE),
+        VaultState::SPACE,
+        program_id,
+    );
+    let state_data = account.data_as_mut_slice();
+    let discriminator: [u8; 8] = anchor_lang::solana_program::hash::hash(b"account:VaultState").to_bytes()[..8].try_into().unwrap();
+    state_data[..8].copy_from_slice(&discriminator);
+    unsafe {
+        let vault_state_ptr = state_data[8..].as_mut_ptr() as *mut VaultState;
+        *vault_state_ptr = vault_state;
+    }
+    account
+}
+
+// Allocates a program owned zero-copy account of type `T` holding `value`
+fn get_zero_copy_account<T: anchor_lang::ZeroCopy>(mollusk: &Mollusk, program_id: &Pubkey, value: &T) -> Account {
+    let space = 8 + std::mem::size_of::<T>();
+    let mut account = Account::new(mollusk.sysvars.rent.minimum_balance(space), space, program_id);
+    let data = account.data_as_mut_slice();
+    data[..8].copy_from_slice(T::DISCRIMINATOR);
+    data[8..].copy_from_slice(bytemuck::bytes_of(value));
+    account
+}
+
+// Program config as bootstrapped by the first initialize, with the default mint policy;
+// fund-moving instructions require it
+fn get_config_account(mollusk: &Mollusk, program_id: &Pubkey, paused: bool) -> (Pubkey, Account) {
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], program_id);
+    let config = ProgramConfig {
+        admin: Pubkey::new_unique(),
+        paused: paused as u8,
+        bump: config_bump,
+        ..Default::default()
+    };
+    (config_pda, get_zero_copy_account(mollusk, program_id, &config))
+}
+
+// PDA that signs the self-CPI `emit_cpi!` logs events through; appended after the named accounts
+fn event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
 
 pub fn init_mollusk() -> (Mollusk, Pubkey, Pubkey, Pubkey, AccountSharedData, AccountSharedData) {
     // Copied from lib.rs
@@ -58,6 +172,7 @@ pub fn init_mollusk() -> (Mollusk, Pubkey, Pubkey, Pubkey, AccountSharedData, Ac
     let mut mollusk = Mollusk::new(&program_id, "../../target/deploy/anchor");
 
     mollusk_svm_programs_token::token::add_program(&mut mollusk);
+    mollusk_svm_programs_token::token2022::add_program(&mut mollusk);
     mollusk_svm_programs_token::associated_token::add_program(&mut mollusk);
 
     let user = Pubkey::new_unique();
@@ -84,6 +199,7 @@ fn test_initialize_vault() {
     let rent_sysvar = solana_sdk::sysvar::rent::id();
 
     let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
 
     //Derive vault state PDA
     let (vault_state_pda, _) =
@@ -94,7 +210,7 @@ fn test_initialize_vault() {
         Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
 
     //Initialize Acounts
-    let user_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+    let user_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
     //Before being initialized the owne of the account is the system program
     let state_account = Account::new(0, 0, &system_program);
     let vault_account = Account::new(0, 0, &system_program);
@@ -106,20 +222,24 @@ fn test_initialize_vault() {
 
     let ix_accounts = vec![
         AccountMeta::new(user, true),
+        AccountMeta::new(user, true), // payer
         AccountMeta::new(vault_state_pda, false),
         AccountMeta::new(vault_account_pda, false),
         AccountMeta::new(token_mint, false),
         AccountMeta::new_readonly(system_program, false),
         AccountMeta::new_readonly(token_program, false),
         AccountMeta::new_readonly(rent_sysvar, false),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
     ];
 
-    let data = (anchor::instruction::Initialize {}).data();
+    let data = (anchor::instruction::Initialize { unlock_ts: 0 }).data();
 
     //Create the initialize instruction
     let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
 
-    let tx_accounts = &vec![
+    let tx_accounts = &[
         (user, user_account.clone()),
         (vault_state_pda, state_account.clone()),
         (vault_account_pda, vault_account.clone()),
@@ -127,9 +247,11 @@ fn test_initialize_vault() {
         (system_program, system_account.clone()),
         (token_program, token_program_account.clone()),
         (rent_sysvar, rent_account.clone().into()),
+        (config_pda, config_account),
+        (event_authority(&program_id), Account::default()),
     ];
 
-    let checks = &vec![Check::success()];
+    let checks = &[Check::success()];
 
     let _deposit_result =
         mollusk.process_and_validate_instruction(&instruction, tx_accounts, checks);
@@ -140,6 +262,7 @@ fn test_initialize_vault() {
 #[test]
 fn test_deposit_vault() {
     let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
 
     let (system_program, _) = program::keyed_account_for_system_program();
     let (token_program, token_program_account) = keyed_account_for_token_program();
@@ -165,10 +288,10 @@ fn test_deposit_vault() {
     let initial_vault_state = anchor::state::VaultState {
         user,
         mint: token_mint,
-        bump: state_bump as u8,
-        bump_token_account: vault_account_bump as u8,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
         deposited: 0,
-        _padding: [0; 6], // Padding to ensure the size is correct
+        ..Default::default()
     };
     
     // Get data allocated in state_account
@@ -204,6 +327,12 @@ fn test_deposit_vault() {
         AccountMeta::new(vault_account_pda, false),
         AccountMeta::new_readonly(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
     ];
 
     let data = (anchor::instruction::Deposit { amount: deposit_amount }).data();
@@ -211,13 +340,15 @@ fn test_deposit_vault() {
     // Create the deposit instruction
     let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
 
-    let tx_accounts = &vec![
+    let tx_accounts = &[
         (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
         (user_ata, user_token_account.clone().into()), // user's token account
         (vault_state_pda, vault_state_account.clone()),
         (vault_account_pda, vault_token_account.clone().into()),
         (token_mint, token_mint_account.clone().into()),
         (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
     ];
 
     // Process the instruction
@@ -260,6 +391,17 @@ fn test_deposit_vault() {
         deposit_amount,
         "Vault state deposited amount should be updated"
     );
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.event_seq, 1, "Each emitted event should bump the vault's sequence");
+    // First link of the audit chain: H(zeros, Deposit, amount, slot)
+    let expected_hash = hashv(&[
+        &[0u8; 32],
+        &[AuditOp::Deposit as u8],
+        &deposit_amount.to_le_bytes(),
+        &mollusk.sysvars.clock.slot.to_le_bytes(),
+    ]);
+    assert_eq!(vault_state_after.audit_count, 1);
+    assert_eq!(vault_state_after.audit_hash, expected_hash.to_bytes(), "Deposit should be chained into the audit hash");
 
     println!("Deposit successful!");
     println!("User balance: {} -> {}", initial_user_balance, user_balance_after);
@@ -269,6 +411,7 @@ fn test_deposit_vault() {
 #[test]
 fn test_withdraw_vault() {
     let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
 
     let (system_program, _) = program::keyed_account_for_system_program();
     let (token_program, token_program_account) = keyed_account_for_token_program();
@@ -295,10 +438,10 @@ fn test_withdraw_vault() {
     let initial_vault_state = anchor::state::VaultState {
         user,
         mint: token_mint,
-        bump: state_bump as u8,
-        bump_token_account: vault_account_bump as u8,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
         deposited: existing_deposit,
-        _padding: [0; 6],
+        ..Default::default()
     };
     
     // Get data allocated in state_account
@@ -333,6 +476,13 @@ fn test_withdraw_vault() {
         AccountMeta::new(vault_account_pda, false),
         AccountMeta::new_readonly(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(program_id, false), // allowlist (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
     ];
 
     let data = (anchor::instruction::Withdraw { amount: withdraw_amount }).data();
@@ -340,13 +490,15 @@ fn test_withdraw_vault() {
     // Create the withdraw instruction
     let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
 
-    let tx_accounts = &vec![
+    let tx_accounts = &[
         (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
         (user_ata, user_token_account.clone().into()),
         (vault_state_pda, vault_state_account.clone()),
         (vault_account_pda, vault_token_account.clone().into()),
         (token_mint, token_mint_account.clone().into()),
         (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
     ];
 
     // Process the instruction
@@ -396,8 +548,9 @@ fn test_withdraw_vault() {
 }
 
 #[test]
-fn test_close_vault() {
+fn test_sweep_and_close() {
     let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
 
     let (system_program, system_account) = program::keyed_account_for_system_program();
     let (token_program, token_program_account) = keyed_account_for_token_program();
@@ -424,10 +577,10 @@ fn test_close_vault() {
     let initial_vault_state = anchor::state::VaultState {
         user,
         mint: token_mint,
-        bump: state_bump as u8,
-        bump_token_account: vault_account_bump as u8,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
         deposited: remaining_deposit,
-        _padding: [0; 6],
+        ..Default::default()
     };
     
     // Get data allocated in state_account
@@ -457,12 +610,18 @@ fn test_close_vault() {
         AccountMeta::new(user_ata, false),
         AccountMeta::new(vault_state_pda, false),
         AccountMeta::new(vault_account_pda, false),
-        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new(token_mint, false),
         AccountMeta::new_readonly(token_program, false),
         AccountMeta::new_readonly(system_program, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(program_id, false), // allowlist (none)
+        AccountMeta::new(user, false), // rent_payer
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
     ];
 
-    let data = (anchor::instruction::CloseVault {}).data();
+    let data = (anchor::instruction::SweepAndClose {}).data();
 
     // Create the close instruction
     let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
@@ -472,7 +631,7 @@ fn test_close_vault() {
     let vault_state_lamports = vault_state_account.lamports();
     let vault_account_lamports = vault_token_account.lamports();
 
-    let tx_accounts = &vec![
+    let tx_accounts = &[
         (user, Account::new(initial_user_lamports, 0, &system_program)),
         (user_ata, user_token_account.clone().into()),
         (vault_state_pda, vault_state_account.clone()),
@@ -480,13 +639,15 @@ fn test_close_vault() {
         (token_mint, token_mint_account.clone().into()),
         (token_program, token_program_account.clone()),
         (system_program, system_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
     ];
 
     // Process the instruction
     let result = mollusk.process_instruction(&instruction, tx_accounts);
 
     // Verify success
-    assert!(!result.program_result.is_err(), "Close vault instruction failed");
+    assert!(!result.program_result.is_err(), "Sweep and close instruction failed");
 
     // Check that all tokens were transferred to user
     let user_token_account_after = result.get_account(&user_ata).unwrap();
@@ -520,8 +681,3714 @@ fn test_close_vault() {
         "User should receive rent from closed accounts"
     );
 
-    println!("Close vault successful!");
+    println!("Sweep and close successful!");
     println!("User token balance: {} -> {}", initial_user_balance, user_balance_after);
     println!("Tokens recovered from vault: {}", remaining_deposit);
     println!("Rent recovered: {} lamports", vault_state_lamports + vault_account_lamports);
 }
+
+#[test]
+fn test_initialize_vault_token_2022() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_2022_program();
+    let rent_sysvar = solana_sdk::sysvar::rent::id();
+
+    let (vault_state_pda, _) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, _) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let token_mint_account = get_mint_account_2022(&user, 5_000_000_000, Some(100));
+    let rent_account = solana_sdk::account::create_account_shared_data_for_test(
+        &solana_sdk::sysvar::rent::Rent::default()
+    );
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user, true), // payer
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new(token_mint, false),
+        AccountMeta::new_readonly(system_program, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(rent_sysvar, false),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Initialize { unlock_ts: 0 }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, Account::new(0, 0, &system_program)),
+        (vault_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.into()),
+        (system_program, system_account.clone()),
+        (token_program, token_program_account.clone()),
+        (rent_sysvar, rent_account.into()),
+        (config_pda, config_account),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let checks = &[
+        Check::success(),
+        Check::account(&vault_account_pda).owner(&token_program).build(),
+    ];
+
+    mollusk.process_and_validate_instruction(&instruction, tx_accounts, checks);
+}
+
+// Runs a deposit against a Token-2022 mint and returns (vault balance, deposited) afterwards
+fn deposit_token_2022(fee_basis_points: Option<u16>, deposit_amount: u64) -> (u64, u64) {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_2022_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 0,
+        ..Default::default()
+    });
+
+    let transfer_fee = fee_basis_points.is_some();
+    let token_mint_account = get_mint_account_2022(&user, 5_000_000_000, fee_basis_points);
+    let user_token_account = get_token_account_2022(&user, &token_mint, 10_000_000, transfer_fee);
+    let vault_token_account = get_token_account_2022(&vault_state_pda, &token_mint, 0, transfer_fee);
+
+    let user_ata = spl_associated_token_account::get_associated_token_address_with_program_id(&user, &token_mint, &token_program);
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user_ata, false),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Deposit { amount: deposit_amount }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, vault_token_account.into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Token-2022 deposit instruction failed");
+
+    let vault_token_account_after = result.get_account(&vault_account_pda).unwrap();
+    let vault_balance_after = u64::from_le_bytes(
+        vault_token_account_after.data[64..72].try_into().unwrap()
+    );
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let deposited_amount = u64::from_le_bytes(
+        vault_state_after.data[72..80].try_into().unwrap()
+    );
+    (vault_balance_after, deposited_amount)
+}
+
+#[test]
+fn test_deposit_vault_token_2022() {
+    let deposit_amount = 5_000_000;
+    let (vault_balance, deposited) = deposit_token_2022(None, deposit_amount);
+
+    assert_eq!(vault_balance, deposit_amount, "Vault token balance should increase by deposit amount");
+    assert_eq!(deposited, deposit_amount, "Vault state deposited amount should be updated");
+}
+
+#[test]
+fn test_deposit_vault_token_2022_transfer_fee() {
+    // 1% fee, the vault only receives 99% of the transfer
+    let deposit_amount = 5_000_000;
+    let (vault_balance, deposited) = deposit_token_2022(Some(100), deposit_amount);
+
+    assert_eq!(vault_balance, 4_950_000, "Vault token balance should exclude the withheld fee");
+    assert_eq!(deposited, vault_balance, "Deposited should match what the vault received");
+}
+
+#[test]
+fn test_withdraw_vault_token_2022() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_2022_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        ..Default::default()
+    });
+
+    let token_mint_account = get_mint_account_2022(&user, 5_000_000_000, None);
+    let user_token_account = get_token_account_2022(&user, &token_mint, 2_000_000, false);
+    let vault_token_account = get_token_account_2022(&vault_state_pda, &token_mint, existing_deposit, false);
+
+    let user_ata = spl_associated_token_account::get_associated_token_address_with_program_id(&user, &token_mint, &token_program);
+    let withdraw_amount = 1_000_000;
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user_ata, false),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(program_id, false), // allowlist (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Withdraw { amount: withdraw_amount }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, vault_token_account.into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Token-2022 withdraw instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let deposited_amount = u64::from_le_bytes(
+        vault_state_after.data[72..80].try_into().unwrap()
+    );
+    assert_eq!(deposited_amount, existing_deposit - withdraw_amount, "Vault state deposited amount should be updated");
+}
+
+// Runs initialize for `token_mint_account` with `policy` set in the config and checks the outcome
+fn initialize_with_policy(token_mint_account: AccountSharedData, policy: MintPolicy, checks: &[Check]) {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let mut config = ProgramConfig {
+        admin: Pubkey::new_unique(),
+        bump: config_bump,
+        ..Default::default()
+    };
+    config.set_mint_policy(&policy);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = if token_mint_account.owner() == &spl_token_2022::id() {
+        keyed_account_for_token_2022_program()
+    } else {
+        keyed_account_for_token_program()
+    };
+    let rent_sysvar = solana_sdk::sysvar::rent::id();
+
+    let (vault_state_pda, _) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, _) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let rent_account = solana_sdk::account::create_account_shared_data_for_test(
+        &solana_sdk::sysvar::rent::Rent::default()
+    );
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user, true), // payer
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new(token_mint, false),
+        AccountMeta::new_readonly(system_program, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(rent_sysvar, false),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Initialize { unlock_ts: 0 }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, Account::new(0, 0, &system_program)),
+        (vault_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.into()),
+        (system_program, system_account.clone()),
+        (token_program, token_program_account.clone()),
+        (rent_sysvar, rent_account.into()),
+        (config_pda, get_zero_copy_account(&mollusk, &program_id, &config)),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(&instruction, tx_accounts, checks);
+}
+
+fn policy_error(error: anchor::error::ErrorCode) -> Check<'static> {
+    Check::err(ProgramError::Custom(error.into()))
+}
+
+#[test]
+fn test_initialize_rejects_freeze_authority() {
+    let authority = Pubkey::new_unique();
+    let mut token_mint_account = AccountSharedData::new(0, Mint::LEN, &spl_token::id());
+    Mint {
+        mint_authority: COption::Some(authority),
+        supply: 5_000_000_000,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::Some(authority),
+    }
+    .pack_into_slice(token_mint_account.data_as_mut_slice());
+
+    let policy = MintPolicy { reject_freeze_authority: true, ..MintPolicy::default() };
+    initialize_with_policy(token_mint_account.clone(), policy, &[policy_error(anchor::error::ErrorCode::MintHasFreezeAuthority)]);
+
+    // The same mint is accepted when the policy tolerates a freeze authority
+    initialize_with_policy(token_mint_account, MintPolicy::default(), &[Check::success()]);
+}
+
+#[test]
+fn test_initialize_rejects_decimals_out_of_range() {
+    let token_mint_account = get_mint_account(&Pubkey::new_unique(), 5_000_000_000);
+
+    // Test mints use 9 decimals
+    let policy = MintPolicy { min_decimals: 0, max_decimals: 6, ..MintPolicy::default() };
+    initialize_with_policy(token_mint_account.clone(), policy, &[policy_error(anchor::error::ErrorCode::MintDecimalsOutOfRange)]);
+
+    let policy = MintPolicy { min_decimals: 6, max_decimals: 9, ..MintPolicy::default() };
+    initialize_with_policy(token_mint_account, policy, &[Check::success()]);
+}
+
+#[test]
+fn test_initialize_rejects_permanent_delegate() {
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::PermanentDelegate]).unwrap();
+    let mut token_mint_account = AccountSharedData::new(0, space, &spl_token_2022::id());
+
+    let data = token_mint_account.data_as_mut_slice();
+    let mut state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(data).unwrap();
+    state.init_extension::<PermanentDelegate>(true).unwrap().delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: COption::Some(Pubkey::new_unique()),
+        supply: 5_000_000_000,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    let policy = MintPolicy { reject_permanent_delegate: true, ..MintPolicy::default() };
+    initialize_with_policy(token_mint_account, policy, &[policy_error(anchor::error::ErrorCode::MintHasPermanentDelegate)]);
+}
+
+#[test]
+fn test_pool_and_basket_mints_follow_policy() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    // Test mints use 9 decimals
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = |policy: MintPolicy| {
+        let mut config = ProgramConfig {
+            admin: Pubkey::new_unique(),
+            bump: config_bump,
+            ..Default::default()
+        };
+        config.set_mint_policy(&policy);
+        get_zero_copy_account(&mollusk, &program_id, &config)
+    };
+    let strict = MintPolicy { min_decimals: 0, max_decimals: 6, ..MintPolicy::default() };
+
+    let (pool_pda, _) = Pubkey::find_program_address(&["pool".as_ref(), token_mint.as_ref()], &program_id);
+    let (pool_account_pda, _) =
+        Pubkey::find_program_address(&["pool_account".as_ref(), pool_pda.as_ref()], &program_id);
+    let initialize_pool = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::InitializePool {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(pool_pda, false),
+            AccountMeta::new(pool_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+        ],
+    );
+    let pool_accounts = |policy: MintPolicy| vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (pool_pda, Account::new(0, 0, &system_program)),
+        (pool_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.clone().into()),
+        (system_program, system_account.clone()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account(policy)),
+    ];
+    mollusk.process_and_validate_instruction(
+        &initialize_pool,
+        &pool_accounts(strict),
+        &[policy_error(anchor::error::ErrorCode::MintDecimalsOutOfRange)],
+    );
+    mollusk.process_and_validate_instruction(&initialize_pool, &pool_accounts(MintPolicy::default()), &[Check::success()]);
+
+    let (basket_pda, basket_bump) =
+        Pubkey::find_program_address(&["basket".as_ref(), user.as_ref()], &program_id);
+    let (basket_account_pda, _) =
+        Pubkey::find_program_address(&["basket_account".as_ref(), basket_pda.as_ref(), token_mint.as_ref()], &program_id);
+    let basket_account = get_zero_copy_account(&mollusk, &program_id, &BasketState {
+        user,
+        token_program,
+        bump: basket_bump,
+        ..Default::default()
+    });
+    let add_basket_mint = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::AddBasketMint {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(basket_pda, false),
+            AccountMeta::new(basket_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+        ],
+    );
+    let basket_accounts = |policy: MintPolicy| vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (basket_pda, basket_account.clone()),
+        (basket_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.clone().into()),
+        (token_program, token_program_account.clone()),
+        (system_program, system_account.clone()),
+        (config_pda, config_account(policy)),
+    ];
+    mollusk.process_and_validate_instruction(
+        &add_basket_mint,
+        &basket_accounts(strict),
+        &[policy_error(anchor::error::ErrorCode::MintDecimalsOutOfRange)],
+    );
+    mollusk.process_and_validate_instruction(&add_basket_mint, &basket_accounts(MintPolicy::default()), &[Check::success()]);
+}
+
+#[test]
+fn test_set_mint_policy_is_admin_only() {
+    let (mollusk, program_id, user, _, _, _) = init_mollusk();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let admin = Pubkey::new_unique();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = get_zero_copy_account(&mollusk, &program_id, &ProgramConfig {
+        admin,
+        bump: config_bump,
+        ..Default::default()
+    });
+
+    // Native SOL has 9 decimals, outside this range
+    let policy = MintPolicy { min_decimals: 0, max_decimals: 6, ..MintPolicy::default() };
+    let set_mint_policy = |signer: Pubkey, policy: MintPolicy| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::SetMintPolicy { policy }).data(),
+        vec![
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new(config_pda, false),
+        ],
+    );
+    let tx_accounts = |signer: Pubkey| vec![
+        (signer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (config_pda, config_account.clone()),
+    ];
+
+    // Vault owners cannot loosen or tighten the policy for themselves
+    mollusk.process_and_validate_instruction(
+        &set_mint_policy(user, policy),
+        &tx_accounts(user),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &set_mint_policy(admin, MintPolicy { min_decimals: 7, max_decimals: 6, ..MintPolicy::default() }),
+        &tx_accounts(admin),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::InvalidArgument.into()))],
+    );
+
+    let result = mollusk.process_instruction(&set_mint_policy(admin, policy), &tx_accounts(admin));
+    assert!(!result.program_result.is_err(), "Admin set_mint_policy failed");
+    let config_after = result.get_account(&config_pda).unwrap().clone();
+    let stored: ProgramConfig = bytemuck::pod_read_unaligned(&config_after.data[8..]);
+    assert_eq!(stored.mint_policy(), policy);
+
+    // initialize_native reads the stored policy too
+    let (vault_state_pda, _) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), Pubkey::default().as_ref()], &program_id);
+    let initialize_native = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::InitializeNative { unlock_ts: 0 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let native_accounts = |config: Account| vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, Account::new(0, 0, &system_program)),
+        (system_program, system_account.clone()),
+        (config_pda, config),
+        (event_authority(&program_id), Account::default()),
+    ];
+    mollusk.process_and_validate_instruction(
+        &initialize_native,
+        &native_accounts(config_after),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MintDecimalsOutOfRange.into()))],
+    );
+    mollusk.process_and_validate_instruction(&initialize_native, &native_accounts(config_account.clone()), &[Check::success()]);
+}
+
+#[test]
+fn test_deposit_mints_receipts() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (receipt_mint_pda, receipt_mint_bump) =
+        Pubkey::find_program_address(&["receipt_mint".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        bump_receipt_mint: receipt_mint_bump,
+        receipt_mint_enabled: 1,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let user_receipt_ata = spl_associated_token_account::get_associated_token_address(&user, &receipt_mint_pda);
+    let deposit_amount = 5_000_000;
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user_ata, false),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new(receipt_mint_pda, false),
+        AccountMeta::new(user_receipt_ata, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Deposit { amount: deposit_amount }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 0).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (receipt_mint_pda, get_mint_account(&vault_state_pda, 0).into()),
+        (user_receipt_ata, get_token_account(&user, &receipt_mint_pda, 0).into()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Deposit with receipts failed");
+
+    let user_receipt_after = result.get_account(&user_receipt_ata).unwrap();
+    let receipt_balance = u64::from_le_bytes(user_receipt_after.data[64..72].try_into().unwrap());
+    assert_eq!(receipt_balance, deposit_amount, "Receipts should be minted 1:1 with the deposit");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let deposited_amount = u64::from_le_bytes(vault_state_after.data[72..80].try_into().unwrap());
+    assert_eq!(deposited_amount, deposit_amount, "Vault state deposited amount should be updated");
+
+    // Leaving the receipt accounts out is refused once the vault has a receipt mint
+    let mut instruction = instruction;
+    instruction.accounts[6] = AccountMeta::new_readonly(program_id, false);
+    instruction.accounts[7] = AccountMeta::new_readonly(program_id, false);
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MissingReceiptAccounts.into()))],
+    );
+}
+
+#[test]
+fn test_redeem_by_receipt_holder() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (receipt_mint_pda, receipt_mint_bump) =
+        Pubkey::find_program_address(&["receipt_mint".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        bump_receipt_mint: receipt_mint_bump,
+        receipt_mint_enabled: 1,
+        ..Default::default()
+    });
+
+    // The owner's receipts have moved to another wallet
+    let holder = Pubkey::new_unique();
+    let holder_ata = spl_associated_token_account::get_associated_token_address(&holder, &token_mint);
+    let holder_receipt_ata = spl_associated_token_account::get_associated_token_address(&holder, &receipt_mint_pda);
+    let redeem_amount = 3_000_000;
+
+    let ix_accounts = vec![
+        AccountMeta::new_readonly(holder, true),
+        AccountMeta::new(holder_ata, false),
+        AccountMeta::new(holder_receipt_ata, false),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new(receipt_mint_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Redeem { amount: redeem_amount }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (holder, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (holder_ata, get_token_account(&holder, &token_mint, 0).into()),
+        (holder_receipt_ata, get_token_account(&holder, &receipt_mint_pda, existing_deposit).into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (receipt_mint_pda, get_mint_account(&vault_state_pda, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Redeem instruction failed");
+
+    let holder_after = result.get_account(&holder_ata).unwrap();
+    let holder_balance = u64::from_le_bytes(holder_after.data[64..72].try_into().unwrap());
+    assert_eq!(holder_balance, redeem_amount, "Holder should receive the redeemed underlying");
+
+    let holder_receipt_after = result.get_account(&holder_receipt_ata).unwrap();
+    let receipt_balance = u64::from_le_bytes(holder_receipt_after.data[64..72].try_into().unwrap());
+    assert_eq!(receipt_balance, existing_deposit - redeem_amount, "Redeemed receipts should be burned");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let deposited_amount = u64::from_le_bytes(vault_state_after.data[72..80].try_into().unwrap());
+    assert_eq!(deposited_amount, existing_deposit - redeem_amount, "Vault state deposited amount should be updated");
+}
+
+#[test]
+fn test_redeem_requires_multisig_threshold() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (receipt_mint_pda, receipt_mint_bump) =
+        Pubkey::find_program_address(&["receipt_mint".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (multisig_pda, multisig_bump) =
+        Pubkey::find_program_address(&["multisig".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        bump_receipt_mint: receipt_mint_bump,
+        receipt_mint_enabled: 1,
+        multisig_enabled: 1,
+        ..Default::default()
+    });
+
+    // 2-of-2: the owner, who still holds every receipt, and one co-signer
+    let cosigner = Pubkey::new_unique();
+    let mut multisig = VaultMultisig {
+        vault: vault_state_pda,
+        signer_count: 2,
+        threshold: 2,
+        bump: multisig_bump,
+        ..Default::default()
+    };
+    multisig.signers[..2].copy_from_slice(&[user, cosigner]);
+    let multisig_account = get_zero_copy_account(&mollusk, &program_id, &multisig);
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let user_receipt_ata = spl_associated_token_account::get_associated_token_address(&user, &receipt_mint_pda);
+
+    let redeem = |cosigners: &[Pubkey]| {
+        let mut ix_accounts = vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(user_receipt_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(receipt_mint_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+            AccountMeta::new_readonly(multisig_pda, false),
+        ];
+        ix_accounts.extend(cosigners.iter().map(|key| AccountMeta::new_readonly(*key, true)));
+        Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::Redeem { amount: existing_deposit }).data(),
+            ix_accounts,
+        )
+    };
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, get_token_account(&user, &token_mint, 0).into()),
+        (user_receipt_ata, get_token_account(&user, &receipt_mint_pda, existing_deposit).into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (receipt_mint_pda, get_mint_account(&vault_state_pda, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (multisig_pda, multisig_account),
+        (cosigner, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // Holding the receipts does not let one key drain the vault
+    mollusk.process_and_validate_instruction(
+        &redeem(&[]),
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MultisigThresholdNotMet.into()))],
+    );
+
+    mollusk.process_and_validate_instruction(&redeem(&[cosigner]), tx_accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_receipt_mint_and_multisig_are_exclusive() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (_, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (receipt_mint_pda, receipt_mint_bump) =
+        Pubkey::find_program_address(&["receipt_mint".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (multisig_pda, _) =
+        Pubkey::find_program_address(&["multisig".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // A receipt vault refuses multisig control, since any holder can redeem
+    let receipt_vault = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        bump_receipt_mint: receipt_mint_bump,
+        receipt_mint_enabled: 1,
+        ..Default::default()
+    });
+    let enable_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::EnableMultisig { signers: vec![user, Pubkey::new_unique()], threshold: 2 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    mollusk.process_and_validate_instruction(
+        &enable_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, receipt_vault),
+            (multisig_pda, Account::default()),
+            (system_program, system_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MultisigUnsupported.into()))],
+    );
+
+    // A multisig vault refuses a receipt mint, which would let holders withdraw without the signers
+    let multisig_vault = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        multisig_enabled: 1,
+        ..Default::default()
+    });
+    let create_instruction = Instruction::new_with_bytes(
+        program_id,
+        &anchor::instruction::CreateReceiptMint {}.data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(receipt_mint_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    mollusk.process_and_validate_instruction(
+        &create_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, multisig_vault),
+            (receipt_mint_pda, Account::default()),
+            (token_mint, token_mint_account.into()),
+            (system_program, system_account),
+            (token_program, token_program_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MultisigUnsupported.into()))],
+    );
+}
+
+#[test]
+fn test_pool_share_conversion_favours_pool() {
+    // Empty pool: the first depositor gets shares 1:1
+    let pool = PoolState::default();
+    assert_eq!(pool.convert_to_shares(1_000, 0).unwrap(), 1_000);
+
+    // 1_000 shares backed by 1_500 assets after yield
+    let pool = PoolState { total_shares: 1_000, ..Default::default() };
+    // 100 assets buy 100 * 1_001 / 1_501 = 66.68.. shares, rounded down
+    assert_eq!(pool.convert_to_shares(100, 1_500).unwrap(), 66);
+    // 66 shares redeem 66 * 1_501 / 1_001 = 98.96.. assets, rounded down
+    assert_eq!(pool.convert_to_assets(66, 1_500).unwrap(), 98);
+
+    // Round-tripping never returns more than was put in
+    for amount in [1, 7, 333, 999_999] {
+        let shares = pool.convert_to_shares(amount, 1_500).unwrap();
+        assert!(pool.convert_to_assets(shares, 1_500).unwrap() <= amount);
+    }
+}
+
+#[test]
+fn test_pool_deposit_accrues_donations_pro_rata() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (pool_state_pda, pool_bump) =
+        Pubkey::find_program_address(&["pool".as_ref(), token_mint.as_ref()], &program_id);
+    let (pool_account_pda, pool_account_bump) =
+        Pubkey::find_program_address(&["pool_account".as_ref(), pool_state_pda.as_ref()], &program_id);
+    let (position_pda, position_bump) =
+        Pubkey::find_program_address(&["position".as_ref(), pool_state_pda.as_ref(), user.as_ref()], &program_id);
+
+    // Another depositor holds 1_000_000 shares, and the pool has since doubled through donations
+    let pool_state_account = get_zero_copy_account(&mollusk, &program_id, &PoolState {
+        mint: token_mint,
+        total_shares: 1_000_000,
+        bump: pool_bump,
+        bump_token_account: pool_account_bump,
+        ..Default::default()
+    });
+    let position_account = get_zero_copy_account(&mollusk, &program_id, &PoolPosition {
+        pool: pool_state_pda,
+        owner: user,
+        shares: 0,
+        bump: position_bump,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let deposit_amount = 1_000_000;
+
+    let ix_accounts = vec![
+        AccountMeta::new_readonly(user, true),
+        AccountMeta::new(user_ata, false),
+        AccountMeta::new(pool_state_pda, false),
+        AccountMeta::new(pool_account_pda, false),
+        AccountMeta::new(position_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::PoolDeposit { amount: deposit_amount }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (pool_state_pda, pool_state_account),
+        (pool_account_pda, get_token_account(&pool_state_pda, &token_mint, 2_000_000).into()),
+        (position_pda, position_account),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Pool deposit instruction failed");
+
+    // 1_000_000 * 1_000_001 / 2_000_001 = 500_000.25.., rounded down
+    let position_after = result.get_account(&position_pda).unwrap();
+    let position_after: PoolPosition = bytemuck::pod_read_unaligned(&position_after.data[8..]);
+    assert_eq!(position_after.shares, 500_000, "Depositor should get shares at the post-donation price");
+
+    let pool_after = result.get_account(&pool_state_pda).unwrap();
+    let pool_after: PoolState = bytemuck::pod_read_unaligned(&pool_after.data[8..]);
+    assert_eq!(pool_after.total_shares, 1_500_000, "Pool total shares should include the new shares");
+}
+
+#[test]
+fn test_pool_withdraw_burns_shares_and_close_position() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (pool_state_pda, pool_bump) =
+        Pubkey::find_program_address(&["pool".as_ref(), token_mint.as_ref()], &program_id);
+    let (pool_account_pda, pool_account_bump) =
+        Pubkey::find_program_address(&["pool_account".as_ref(), pool_state_pda.as_ref()], &program_id);
+    let (position_pda, position_bump) =
+        Pubkey::find_program_address(&["position".as_ref(), pool_state_pda.as_ref(), user.as_ref()], &program_id);
+
+    // The user holds 500_000 of 1_500_000 shares over 3_000_000 assets
+    let pool_state_account = get_zero_copy_account(&mollusk, &program_id, &PoolState {
+        mint: token_mint,
+        total_shares: 1_500_000,
+        bump: pool_bump,
+        bump_token_account: pool_account_bump,
+        ..Default::default()
+    });
+    let position_account = get_zero_copy_account(&mollusk, &program_id, &PoolPosition {
+        pool: pool_state_pda,
+        owner: user,
+        shares: 500_000,
+        bump: position_bump,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let pool_assets = 3_000_000;
+    let withdraw_shares = 200_000;
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::PoolWithdraw { shares: withdraw_shares }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(pool_state_pda, false),
+            AccountMeta::new(pool_account_pda, false),
+            AccountMeta::new(position_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (user_ata, user_token_account.into()),
+            (pool_state_pda, pool_state_account.clone()),
+            (pool_account_pda, get_token_account(&pool_state_pda, &token_mint, pool_assets).into()),
+            (position_pda, position_account),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+    );
+    assert!(!result.program_result.is_err(), "Pool withdraw instruction failed");
+
+    // 200_000 * 3_000_001 / 1_500_001 = 399_999.86.., rounded down
+    let expected_assets = 399_999;
+    let user_token_after = result.get_account(&user_ata).unwrap();
+    let user_balance = u64::from_le_bytes(user_token_after.data[64..72].try_into().unwrap());
+    assert_eq!(user_balance, 10_000_000 + expected_assets, "User should receive the shares' part of the pool");
+
+    let pool_account_after = result.get_account(&pool_account_pda).unwrap();
+    let pool_balance = u64::from_le_bytes(pool_account_after.data[64..72].try_into().unwrap());
+    assert_eq!(pool_balance, pool_assets - expected_assets);
+
+    let position_after = result.get_account(&position_pda).unwrap().clone();
+    let position_state: PoolPosition = bytemuck::pod_read_unaligned(&position_after.data[8..]);
+    assert_eq!(position_state.shares, 300_000, "Withdrawn shares should be burned from the position");
+
+    let pool_after = result.get_account(&pool_state_pda).unwrap();
+    let pool_after: PoolState = bytemuck::pod_read_unaligned(&pool_after.data[8..]);
+    assert_eq!(pool_after.total_shares, 1_300_000, "Withdrawn shares should leave the pool total");
+    assert_eq!(pool_after.event_seq, 1, "Each pool event takes the next sequence number");
+
+    // A position with shares left cannot be closed
+    let close_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::ClosePosition {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(pool_state_pda, false),
+            AccountMeta::new(position_pda, false),
+        ],
+    );
+    mollusk.process_and_validate_instruction(
+        &close_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (pool_state_pda, pool_state_account.clone()),
+            (position_pda, position_after),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::NonZeroBalance.into()))],
+    );
+
+    // Once empty it closes and its rent goes back to the user
+    let empty_position = get_zero_copy_account(&mollusk, &program_id, &PoolPosition {
+        pool: pool_state_pda,
+        owner: user,
+        shares: 0,
+        bump: position_bump,
+        ..Default::default()
+    });
+    let position_rent = empty_position.lamports();
+    let result = mollusk.process_instruction(
+        &close_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (pool_state_pda, pool_state_account),
+            (position_pda, empty_position),
+        ],
+    );
+    assert!(!result.program_result.is_err(), "Close position instruction failed");
+    assert_eq!(result.get_account(&position_pda).unwrap().lamports(), 0, "Position should be closed");
+    assert_eq!(result.get_account(&user).unwrap().lamports(), LAMPORTS_PER_SOL + position_rent);
+}
+
+#[test]
+fn test_withdraw_refused_while_locked() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let unlock_ts = mollusk.sysvars.clock.unix_timestamp + 86_400;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        unlock_ts,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let ix_accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(user_ata, false),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+        AccountMeta::new_readonly(program_id, false), // allowlist (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+
+    let data = (anchor::instruction::Withdraw { amount: 1_000_000 }).data();
+    let instruction = Instruction::new_with_bytes(program_id, &data, ix_accounts);
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultLocked.into()))],
+    );
+
+    // Once the unlock time has passed the same withdraw goes through
+    let mut mollusk = mollusk;
+    mollusk.sysvars.clock.unix_timestamp = unlock_ts;
+    mollusk.process_and_validate_instruction(&instruction, tx_accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_extend_lock_only_moves_later() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        unlock_ts: 1_000,
+        ..Default::default()
+    });
+
+    let extend_lock = |unlock_ts: i64| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::ExtendLock { unlock_ts }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(
+        &extend_lock(500),
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::LockNotExtended.into()))],
+    );
+
+    let result = mollusk.process_instruction(&extend_lock(2_000), tx_accounts);
+    assert!(!result.program_result.is_err(), "Extend lock instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.unlock_ts, 2_000, "Unlock timestamp should be pushed later");
+}
+
+#[test]
+fn test_request_withdraw_reserves_amount() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (withdraw_request_pda, _) =
+        Pubkey::find_program_address(&["withdraw_request".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let cooldown = 3_600;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        withdraw_cooldown: cooldown,
+        ..Default::default()
+    });
+
+    let request_amount = 3_000_000;
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::RequestWithdraw { amount: request_amount }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(vault_account_pda, false),
+            AccountMeta::new(withdraw_request_pda, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account.clone()),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (withdraw_request_pda, Account::new(0, 0, &system_program)),
+        (system_program, system_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // Nothing can be reserved against tokens the vault account no longer holds
+    let mut underfunded_accounts = tx_accounts.to_vec();
+    underfunded_accounts[2].1 = get_token_account(&vault_state_pda, &token_mint, existing_deposit - 1).into();
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &underfunded_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultUnderfunded.into()))],
+    );
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Request withdraw instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.pending_withdraw, request_amount, "Requested amount should be reserved");
+    assert_eq!(vault_state_after.deposited, existing_deposit, "Nothing leaves the vault until the claim");
+
+    let request_after = result.get_account(&withdraw_request_pda).unwrap();
+    let request_after: WithdrawRequest = bytemuck::pod_read_unaligned(&request_after.data[8..]);
+    assert_eq!(request_after.amount, request_amount);
+    assert_eq!(request_after.ready_ts, mollusk.sysvars.clock.unix_timestamp + cooldown);
+
+    // A vault with a cooldown refuses direct withdrawals
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let withdraw_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Withdraw { amount: request_amount }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    mollusk.process_and_validate_instruction(
+        &withdraw_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (user_ata, user_token_account.into()),
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::WithdrawRequiresRequest.into()))],
+    );
+}
+
+#[test]
+fn test_claim_withdraw_after_cooldown() {
+    let (mut mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (withdraw_request_pda, withdraw_request_bump) =
+        Pubkey::find_program_address(&["withdraw_request".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let request_amount = 3_000_000;
+    let ready_ts = mollusk.sysvars.clock.unix_timestamp + 3_600;
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        withdraw_cooldown: 3_600,
+        pending_withdraw: request_amount,
+        ..Default::default()
+    });
+    let withdraw_request_account = get_zero_copy_account(&mollusk, &program_id, &WithdrawRequest {
+        vault: vault_state_pda,
+        amount: request_amount,
+        ready_ts,
+        bump: withdraw_request_bump,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::ClaimWithdraw {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(withdraw_request_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, get_token_account(&user, &token_mint, 0).into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (withdraw_request_pda, withdraw_request_account),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::CooldownNotElapsed.into()))],
+    );
+
+    mollusk.sysvars.clock.unix_timestamp = ready_ts;
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Claim withdraw instruction failed");
+
+    let user_token_after = result.get_account(&user_ata).unwrap();
+    let user_balance = u64::from_le_bytes(user_token_after.data[64..72].try_into().unwrap());
+    assert_eq!(user_balance, request_amount, "User should receive the requested amount");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.deposited, existing_deposit - request_amount);
+    assert_eq!(vault_state_after.pending_withdraw, 0, "Claimed amount should no longer be reserved");
+
+    assert_eq!(
+        result.get_account(&withdraw_request_pda).unwrap().lamports(),
+        0,
+        "Withdraw request should be closed"
+    );
+}
+
+#[test]
+fn test_cancel_withdraw_releases_reservation() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (_, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (withdraw_request_pda, withdraw_request_bump) =
+        Pubkey::find_program_address(&["withdraw_request".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let request_amount = 3_000_000;
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        withdraw_cooldown: 3_600,
+        pending_withdraw: request_amount,
+        ..Default::default()
+    });
+    let withdraw_request_account = get_zero_copy_account(&mollusk, &program_id, &WithdrawRequest {
+        vault: vault_state_pda,
+        amount: request_amount,
+        ready_ts: mollusk.sysvars.clock.unix_timestamp + 3_600,
+        bump: withdraw_request_bump,
+        ..Default::default()
+    });
+    let request_rent = withdraw_request_account.lamports();
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CancelWithdraw {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(withdraw_request_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, vault_state_account),
+            (withdraw_request_pda, withdraw_request_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+    );
+    assert!(!result.program_result.is_err(), "Cancel withdraw instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.pending_withdraw, 0, "Cancelled amount should no longer be reserved");
+    assert_eq!(vault_state_after.deposited, existing_deposit, "Cancelling moves no tokens");
+
+    assert_eq!(result.get_account(&withdraw_request_pda).unwrap().lamports(), 0, "Withdraw request should be closed");
+    assert_eq!(
+        result.get_account(&user).unwrap().lamports(),
+        LAMPORTS_PER_SOL + request_rent,
+        "Request rent should go back to the owner"
+    );
+}
+
+#[test]
+fn test_withdraw_cooldown_refused_on_receipt_vault() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (_, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (receipt_mint_pda, receipt_mint_bump) =
+        Pubkey::find_program_address(&["receipt_mint".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let receipt_vault = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        bump_receipt_mint: receipt_mint_bump,
+        receipt_mint_enabled: 1,
+        ..Default::default()
+    });
+    mollusk.process_and_validate_instruction(
+        &Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::SetWithdrawCooldown { cooldown: 3_600 }).data(),
+            vec![
+                AccountMeta::new_readonly(user, true),
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new_readonly(event_authority(&program_id), false),
+                AccountMeta::new_readonly(program_id, false), // program
+            ],
+        ),
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, receipt_vault),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::CooldownUnsupported.into()))],
+    );
+
+    // Nor can a vault with a cooldown add a receipt mint
+    let cooldown_vault = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        withdraw_cooldown: 3_600,
+        ..Default::default()
+    });
+    mollusk.process_and_validate_instruction(
+        &Instruction::new_with_bytes(
+            program_id,
+            &anchor::instruction::CreateReceiptMint {}.data(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new(receipt_mint_pda, false),
+                AccountMeta::new_readonly(token_mint, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(event_authority(&program_id), false),
+                AccountMeta::new_readonly(program_id, false), // program
+            ],
+        ),
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, cooldown_vault),
+            (receipt_mint_pda, Account::default()),
+            (token_mint, token_mint_account.into()),
+            (system_program, system_account),
+            (token_program, token_program_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::CooldownUnsupported.into()))],
+    );
+}
+
+#[test]
+fn test_withdraw_requires_multisig_threshold() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (multisig_pda, multisig_bump) =
+        Pubkey::find_program_address(&["multisig".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        multisig_enabled: 1,
+        ..Default::default()
+    });
+
+    // 2-of-3: the owner plus two co-signers
+    let cosigner_a = Pubkey::new_unique();
+    let cosigner_b = Pubkey::new_unique();
+    let mut multisig = VaultMultisig {
+        vault: vault_state_pda,
+        signer_count: 3,
+        threshold: 2,
+        bump: multisig_bump,
+        ..Default::default()
+    };
+    multisig.signers[..3].copy_from_slice(&[user, cosigner_a, cosigner_b]);
+    let multisig_account = get_zero_copy_account(&mollusk, &program_id, &multisig);
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let withdraw = |cosigners: &[Pubkey]| {
+        let mut ix_accounts = vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+            AccountMeta::new_readonly(multisig_pda, false),
+        ];
+        ix_accounts.extend(cosigners.iter().map(|key| AccountMeta::new_readonly(*key, true)));
+        Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::Withdraw { amount: 1_000_000 }).data(),
+            ix_accounts,
+        )
+    };
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (multisig_pda, multisig_account),
+        (cosigner_a, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (cosigner_b, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // The owner's signature alone is below the threshold
+    mollusk.process_and_validate_instruction(
+        &withdraw(&[]),
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MultisigThresholdNotMet.into()))],
+    );
+
+    mollusk.process_and_validate_instruction(&withdraw(&[cosigner_a]), tx_accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_accept_owner_rekeys_vault() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let new_owner = Pubkey::new_unique();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (owner_proposal_pda, owner_proposal_bump) =
+        Pubkey::find_program_address(&["owner_proposal".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (new_vault_state_pda, new_state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), new_owner.as_ref(), token_mint.as_ref()], &program_id);
+    let (new_vault_account_pda, new_vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), new_vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let unlock_ts = mollusk.sysvars.clock.unix_timestamp + 86_400;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        unlock_ts,
+        guardian: Pubkey::new_unique(),
+        ..Default::default()
+    });
+    let owner_proposal_account = get_zero_copy_account(&mollusk, &program_id, &OwnerProposal {
+        vault: vault_state_pda,
+        new_owner,
+        bump: owner_proposal_bump,
+        ..Default::default()
+    });
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::AcceptOwner {}).data(),
+        vec![
+            AccountMeta::new(new_owner, true),
+            AccountMeta::new(user, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(owner_proposal_pda, false),
+            AccountMeta::new(new_vault_state_pda, false),
+            AccountMeta::new(new_vault_account_pda, false),
+            AccountMeta::new(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new(user, false), // rent_payer
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (new_owner, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (owner_proposal_pda, owner_proposal_account),
+        (new_vault_state_pda, Account::new(0, 0, &system_program)),
+        (new_vault_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (system_program, system_account),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Accept owner instruction failed");
+
+    let new_vault_state = result.get_account(&new_vault_state_pda).unwrap();
+    let new_vault_state: VaultState = bytemuck::pod_read_unaligned(&new_vault_state.data[8..]);
+    assert_eq!(new_vault_state.user, new_owner);
+    assert_eq!(new_vault_state.bump, new_state_bump);
+    assert_eq!(new_vault_state.bump_token_account, new_vault_account_bump);
+    assert_eq!(new_vault_state.deposited, existing_deposit, "Balance should follow the vault");
+    assert_eq!(new_vault_state.unlock_ts, unlock_ts, "Lock should follow the vault");
+    assert_eq!(new_vault_state.rent_payer, new_owner, "New owner paid the new vault's rent");
+    assert!(!new_vault_state.has_guardian(), "The previous owner's guardian must not carry over");
+    assert_eq!(new_vault_state.unfreeze_ts, 0);
+    assert!(!new_vault_state.is_frozen());
+    // The old vault's chain continues in the new one with the carried balance
+    let expected_hash = hashv(&[
+        &[0u8; 32],
+        &[AuditOp::Transfer as u8],
+        &existing_deposit.to_le_bytes(),
+        &mollusk.sysvars.clock.slot.to_le_bytes(),
+    ]);
+    assert_eq!(new_vault_state.audit_count, 1);
+    assert_eq!(new_vault_state.audit_hash, expected_hash.to_bytes(), "Ownership transfer should be chained into the audit hash");
+
+    let new_vault_account = result.get_account(&new_vault_account_pda).unwrap();
+    let new_balance = u64::from_le_bytes(new_vault_account.data[64..72].try_into().unwrap());
+    assert_eq!(new_balance, existing_deposit);
+
+    for retired in [vault_state_pda, vault_account_pda, owner_proposal_pda] {
+        assert_eq!(result.get_account(&retired).unwrap().lamports(), 0, "Old vault accounts should be closed");
+    }
+}
+
+#[test]
+fn test_deposit_for_credits_owner_vault() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 1_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        ..Default::default()
+    });
+
+    // Anyone can fund the vault from their own token account
+    let depositor = Pubkey::new_unique();
+    let depositor_ata = spl_associated_token_account::get_associated_token_address(&depositor, &token_mint);
+    let deposit_amount = 4_000_000;
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::DepositFor { owner: user, amount: deposit_amount }).data(),
+        vec![
+            AccountMeta::new_readonly(depositor, true),
+            AccountMeta::new(depositor_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // owner_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (depositor, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (depositor_ata, get_token_account(&depositor, &token_mint, deposit_amount).into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Deposit for instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.user, user, "Ownership should not change");
+    assert_eq!(vault_state_after.deposited, existing_deposit + deposit_amount);
+
+    let depositor_after = result.get_account(&depositor_ata).unwrap();
+    let depositor_balance = u64::from_le_bytes(depositor_after.data[64..72].try_into().unwrap());
+    assert_eq!(depositor_balance, 0, "Tokens should come from the depositor");
+}
+
+#[test]
+fn test_native_vault_deposit_and_withdraw() {
+    let (mollusk, program_id, user, _, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let native_mint = Pubkey::default();
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), native_mint.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: native_mint,
+        bump: state_bump,
+        ..Default::default()
+    });
+    let rent_minimum = vault_state_account.lamports;
+
+    let deposit_amount = LAMPORTS_PER_SOL / 2;
+    let deposit = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::DepositNative { amount: deposit_amount }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_instruction(&deposit, &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account),
+        (system_program, system_account),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ]);
+    assert!(!result.program_result.is_err(), "Native deposit instruction failed");
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap().clone();
+    assert_eq!(vault_state_after.lamports, rent_minimum + deposit_amount);
+    let state: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(state.deposited, deposit_amount);
+
+    let withdraw = |amount: u64| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::WithdrawNative { amount }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let tx_accounts = &[
+        (user, result.get_account(&user).unwrap().clone()),
+        (vault_state_pda, vault_state_after),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // The rent-exempt reserve is never available for withdrawal
+    mollusk.process_and_validate_instruction(
+        &withdraw(deposit_amount + 1),
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::InsufficientBalance.into()))],
+    );
+
+    let result = mollusk.process_instruction(&withdraw(deposit_amount), tx_accounts);
+    assert!(!result.program_result.is_err(), "Native withdraw instruction failed");
+    assert_eq!(result.get_account(&vault_state_pda).unwrap().lamports, rent_minimum);
+    assert_eq!(result.get_account(&user).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn test_close_native_vault_requires_zero_balance() {
+    let (mollusk, program_id, user, _, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let native_mint = Pubkey::default();
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), native_mint.as_ref()], &program_id);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CloseNativeVault {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new(user, false), // rent_payer
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    // `extra` lamports sit on top of the rent, `deposited` of them credited to the owner
+    let tx_accounts = |deposited: u64, extra: u64| {
+        let mut vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+            user,
+            mint: native_mint,
+            bump: state_bump,
+            deposited,
+            ..Default::default()
+        });
+        vault_state_account.lamports += extra;
+        vec![
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, vault_state_account),
+            (config_pda, config_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ]
+    };
+
+    // Deposited lamports must be withdrawn first, close no longer pays them out
+    let deposit_amount = LAMPORTS_PER_SOL / 2;
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts(deposit_amount, deposit_amount),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::NonZeroBalance.into()))],
+    );
+
+    // Lamports sent outside deposit_native are not the owner's and cannot block the close
+    let donation = 1_000;
+    let accounts = tx_accounts(0, donation);
+    let vault_lamports = accounts[1].1.lamports;
+    let result = mollusk.process_and_validate_instruction(&instruction, &accounts, &[Check::success()]);
+    assert!(
+        result.get_account(&vault_state_pda).is_none() || result.get_account(&vault_state_pda).unwrap().lamports() == 0,
+        "Empty native vault should be closed"
+    );
+    assert_eq!(result.get_account(&user).unwrap().lamports, LAMPORTS_PER_SOL + vault_lamports);
+}
+
+#[test]
+fn test_basket_deposit_selects_mint_by_index() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let other_mint = Pubkey::new_unique();
+
+    let (basket_pda, basket_bump) =
+        Pubkey::find_program_address(&["basket".as_ref(), user.as_ref()], &program_id);
+    let (_, other_account_bump) =
+        Pubkey::find_program_address(&["basket_account".as_ref(), basket_pda.as_ref(), other_mint.as_ref()], &program_id);
+    let (basket_account_pda, basket_account_bump) =
+        Pubkey::find_program_address(&["basket_account".as_ref(), basket_pda.as_ref(), token_mint.as_ref()], &program_id);
+
+    // `token_mint` sits at index 1
+    let mut basket = BasketState {
+        user,
+        token_program,
+        mint_count: 2,
+        bump: basket_bump,
+        ..Default::default()
+    };
+    basket.mints[..2].copy_from_slice(&[other_mint, token_mint]);
+    basket.bumps_token_account[..2].copy_from_slice(&[other_account_bump, basket_account_bump]);
+    let basket_account = get_zero_copy_account(&mollusk, &program_id, &basket);
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let deposit_amount = 2_000_000;
+
+    let basket_deposit = |index: u8| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::BasketDeposit { index, amount: deposit_amount }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(basket_pda, false),
+            AccountMeta::new(basket_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (basket_pda, basket_account),
+        (basket_account_pda, get_token_account(&basket_pda, &token_mint, 0).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(
+        &basket_deposit(0),
+        tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::InvalidMint.into()))],
+    );
+
+    let result = mollusk.process_instruction(&basket_deposit(1), tx_accounts);
+    assert!(!result.program_result.is_err(), "Basket deposit instruction failed");
+
+    let basket_after = result.get_account(&basket_pda).unwrap();
+    let basket_after: BasketState = bytemuck::pod_read_unaligned(&basket_after.data[8..]);
+    assert_eq!(basket_after.balances[0], 0);
+    assert_eq!(basket_after.balances[1], deposit_amount);
+    assert!(!basket_after.is_empty(), "A funded basket cannot be closed");
+}
+
+#[test]
+fn test_close_basket_requires_empty_basket() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (basket_pda, basket_bump) =
+        Pubkey::find_program_address(&["basket".as_ref(), user.as_ref()], &program_id);
+    let (basket_account_pda, basket_account_bump) =
+        Pubkey::find_program_address(&["basket_account".as_ref(), basket_pda.as_ref(), token_mint.as_ref()], &program_id);
+
+    let deposited = 2_000_000;
+    let mut basket = BasketState {
+        user,
+        token_program,
+        mint_count: 1,
+        bump: basket_bump,
+        ..Default::default()
+    };
+    basket.mints[0] = token_mint;
+    basket.balances[0] = deposited;
+    basket.bumps_token_account[0] = basket_account_bump;
+    let basket_account = get_zero_copy_account(&mollusk, &program_id, &basket);
+    let basket_rent = basket_account.lamports();
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let user_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+    let close_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CloseBasket {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(basket_pda, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+            // remaining accounts: [basket_account, mint, user_account] for index 0
+            AccountMeta::new(basket_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new(user_ata, false),
+        ],
+    );
+
+    let funded_accounts = vec![
+        (user, user_account.clone()),
+        (user_ata, get_token_account(&user, &token_mint, 0).into()),
+        (basket_pda, basket_account),
+        (basket_account_pda, get_token_account(&basket_pda, &token_mint, deposited).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    mollusk.process_and_validate_instruction(
+        &close_instruction,
+        &funded_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::NonZeroBalance.into()))],
+    );
+
+    // Withdrawing the whole balance empties the basket
+    let withdraw_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::BasketWithdraw { index: 0, amount: deposited }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(basket_pda, false),
+            AccountMeta::new(basket_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let result = mollusk.process_instruction(&withdraw_instruction, &funded_accounts);
+    assert!(!result.program_result.is_err(), "Basket withdraw instruction failed");
+
+    let user_token_after = result.get_account(&user_ata).unwrap();
+    let user_balance = u64::from_le_bytes(user_token_after.data[64..72].try_into().unwrap());
+    assert_eq!(user_balance, deposited, "User should receive the whole basket balance");
+
+    let basket_after: BasketState = bytemuck::pod_read_unaligned(&result.get_account(&basket_pda).unwrap().data[8..]);
+    assert!(basket_after.is_empty(), "Withdrawn basket should be empty");
+    assert_eq!(basket_after.event_seq, 1, "Each basket event takes the next sequence number");
+
+    let empty_accounts: Vec<_> = funded_accounts
+        .iter()
+        .map(|(key, account)| (*key, result.get_account(key).cloned().unwrap_or_else(|| account.clone())))
+        .collect();
+    let result = mollusk.process_instruction(&close_instruction, &empty_accounts);
+    assert!(!result.program_result.is_err(), "Close basket instruction failed");
+
+    assert_eq!(result.get_account(&basket_pda).unwrap().lamports(), 0, "Basket should be closed");
+    assert_eq!(result.get_account(&basket_account_pda).unwrap().lamports(), 0, "Basket token account should be closed");
+    assert!(
+        result.get_account(&user).unwrap().lamports() >= LAMPORTS_PER_SOL + basket_rent,
+        "Rent should go back to the user"
+    );
+}
+
+#[test]
+fn test_withdraw_limit_caps_window() {
+    let (mut mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 8_000_000;
+    let window = 86_400;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        withdraw_limit: 1_000_000,
+        withdraw_window: window,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Withdraw { amount: 600_000 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let mut tx_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, user_token_account.into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, &tx_accounts);
+    assert!(!result.program_result.is_err(), "First withdraw within the limit failed");
+
+    // Carry the post-withdraw accounts into the next call
+    for (key, account) in tx_accounts.iter_mut() {
+        *account = result.get_account(key).unwrap().clone();
+    }
+
+    // 600k + 600k is over the 1M allowed in this window
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::WithdrawLimitExceeded.into()))],
+    );
+
+    // A new window resets the amount used
+    mollusk.sysvars.clock.unix_timestamp += window;
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_initialize_bootstraps_config() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+    let rent_sysvar = solana_sdk::sysvar::rent::id();
+
+    let (vault_state_pda, _) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, _) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    // Pays for the vault and the config; `user` still owns the vault and becomes admin
+    let sponsor = Pubkey::new_unique();
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Initialize { unlock_ts: 0 }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(sponsor, true), // payer
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(token_mint, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(rent_sysvar, false),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = &[
+        (user, Account::new(0, 0, &system_program)),
+        (sponsor, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, Account::new(0, 0, &system_program)),
+        (vault_account_pda, Account::new(0, 0, &system_program)),
+        (token_mint, token_mint_account.into()),
+        (system_program, system_account),
+        (token_program, token_program_account.clone()),
+        (rent_sysvar, solana_sdk::account::create_account_shared_data_for_test(&solana_sdk::sysvar::rent::Rent::default()).into()),
+        // Pre-funded by a third party, which must not block the bootstrap
+        (config_pda, Account::new(1_000, 0, &system_program)),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, tx_accounts);
+    assert!(!result.program_result.is_err(), "Initialize instruction failed");
+
+    let config_after = result.get_account(&config_pda).unwrap();
+    assert_eq!(config_after.owner, program_id);
+    let config_after: ProgramConfig = bytemuck::pod_read_unaligned(&config_after.data[8..]);
+    assert_eq!(config_after.admin, user, "First initializer becomes the admin");
+    assert_eq!(config_after.bump, config_bump);
+    assert!(!config_after.is_paused());
+    assert_eq!(config_after.mint_policy(), MintPolicy::default(), "Any mint is accepted until the admin sets a policy");
+
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert_eq!(vault_state_after.user, user);
+    assert_eq!(vault_state_after.rent_payer, sponsor, "Rent goes back to whoever paid for the vault");
+}
+
+#[test]
+fn test_admin_transfer_and_pause_are_admin_only() {
+    let (mollusk, program_id, _, _, _, _) = init_mollusk();
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let admin = Pubkey::new_unique();
+    let new_admin = Pubkey::new_unique();
+    let stranger = Pubkey::new_unique();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = get_zero_copy_account(&mollusk, &program_id, &ProgramConfig {
+        admin,
+        bump: config_bump,
+        ..Default::default()
+    });
+    let unauthorized = [Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))];
+
+    let signed = |signer: Pubkey, data: Vec<u8>| Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![AccountMeta::new_readonly(signer, true), AccountMeta::new(config_pda, false)],
+    );
+    let tx_accounts = |signer: Pubkey, config: Account| vec![
+        (signer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (config_pda, config),
+    ];
+
+    // Only the admin pauses
+    let set_paused = (anchor::instruction::SetPaused { paused: true }).data();
+    mollusk.process_and_validate_instruction(
+        &signed(stranger, set_paused.clone()),
+        &tx_accounts(stranger, config_account.clone()),
+        &unauthorized,
+    );
+    let result = mollusk.process_instruction(&signed(admin, set_paused), &tx_accounts(admin, config_account.clone()));
+    assert!(!result.program_result.is_err(), "Set paused instruction failed");
+    let config_after: ProgramConfig = bytemuck::pod_read_unaligned(&result.get_account(&config_pda).unwrap().data[8..]);
+    assert!(config_after.is_paused());
+
+    // Only the admin proposes a successor
+    let propose_admin = (anchor::instruction::ProposeAdmin { new_admin }).data();
+    mollusk.process_and_validate_instruction(
+        &signed(stranger, propose_admin.clone()),
+        &tx_accounts(stranger, config_account.clone()),
+        &unauthorized,
+    );
+    let result = mollusk.process_instruction(&signed(admin, propose_admin), &tx_accounts(admin, config_account));
+    assert!(!result.program_result.is_err(), "Propose admin instruction failed");
+    let proposed_config = result.get_account(&config_pda).unwrap().clone();
+    let config_after: ProgramConfig = bytemuck::pod_read_unaligned(&proposed_config.data[8..]);
+    assert_eq!(config_after.admin, admin, "Proposing does not hand over control yet");
+    assert_eq!(config_after.pending_admin, new_admin);
+
+    // Only the proposed admin accepts
+    let accept_admin = (anchor::instruction::AcceptAdmin {}).data();
+    mollusk.process_and_validate_instruction(
+        &signed(stranger, accept_admin.clone()),
+        &tx_accounts(stranger, proposed_config.clone()),
+        &unauthorized,
+    );
+    let result = mollusk.process_instruction(&signed(new_admin, accept_admin), &tx_accounts(new_admin, proposed_config));
+    assert!(!result.program_result.is_err(), "Accept admin instruction failed");
+    let config_after: ProgramConfig = bytemuck::pod_read_unaligned(&result.get_account(&config_pda).unwrap().data[8..]);
+    assert_eq!(config_after.admin, new_admin, "Proposed admin should take over");
+    assert_eq!(config_after.pending_admin, Pubkey::default(), "Proposal should be cleared");
+}
+
+#[test]
+fn test_deposit_refused_while_paused() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, true);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Deposit { amount: 1_000_000 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (user_ata, user_token_account.into()),
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 0).into()),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::ProgramPaused.into()))],
+    );
+}
+
+#[test]
+fn test_withdraw_pays_fee_to_treasury() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    // 0.5% withdraw fee
+    let treasury = Pubkey::new_unique();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = get_zero_copy_account(&mollusk, &program_id, &ProgramConfig {
+        admin: Pubkey::new_unique(),
+        bump: config_bump,
+        withdraw_fee_bps: 50,
+        treasury,
+        ..Default::default()
+    });
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 2_000_000,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let treasury_ata = spl_associated_token_account::get_associated_token_address(&treasury, &token_mint);
+
+    let withdraw = |treasury_meta: AccountMeta| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Withdraw { amount: 1_000_000 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            treasury_meta,
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (user_ata, get_token_account(&user, &token_mint, 0).into()),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 2_000_000).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account),
+        (treasury_ata, get_token_account(&treasury, &token_mint, 0).into()),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // A fee is due, so the treasury account cannot be left out
+    mollusk.process_and_validate_instruction(
+        &withdraw(AccountMeta::new_readonly(program_id, false)),
+        &tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::MissingTreasuryAccount.into()))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw(AccountMeta::new(treasury_ata, false)),
+        &tx_accounts,
+        &[Check::success()],
+    );
+
+    let token_amount = |key: &Pubkey| {
+        u64::from_le_bytes(result.get_account(key).unwrap().data[64..72].try_into().unwrap())
+    };
+    assert_eq!(token_amount(&user_ata), 995_000, "Owner receives the net amount");
+    assert_eq!(token_amount(&treasury_ata), 5_000, "Treasury receives the fee");
+    assert_eq!(token_amount(&vault_account_pda), 1_000_000);
+
+    let vault_state_after: VaultState =
+        bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert_eq!(vault_state_after.deposited, 1_000_000, "Deposited drops by the gross amount");
+}
+
+#[test]
+fn test_set_fees_caps_bps_and_is_admin_only() {
+    let (mollusk, program_id, _, _, _, _) = init_mollusk();
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let admin = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = get_zero_copy_account(&mollusk, &program_id, &ProgramConfig {
+        admin,
+        bump: config_bump,
+        ..Default::default()
+    });
+
+    let set_fees = |signer: Pubkey, deposit_fee_bps: u16, withdraw_fee_bps: u16| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::SetFees { deposit_fee_bps, withdraw_fee_bps, treasury }).data(),
+        vec![AccountMeta::new_readonly(signer, true), AccountMeta::new(config_pda, false)],
+    );
+    let tx_accounts = |signer: Pubkey| vec![
+        (signer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (config_pda, config_account.clone()),
+    ];
+
+    // MAX_FEE_BPS itself is accepted
+    let result = mollusk.process_instruction(&set_fees(admin, 100, 100), &tx_accounts(admin));
+    assert!(!result.program_result.is_err(), "Set fees instruction failed");
+    let config_after: ProgramConfig = bytemuck::pod_read_unaligned(&result.get_account(&config_pda).unwrap().data[8..]);
+    assert_eq!(config_after.deposit_fee_bps, 100);
+    assert_eq!(config_after.withdraw_fee_bps, 100);
+    assert_eq!(config_after.treasury, treasury);
+
+    // One basis point over fails, on either fee
+    for (deposit_fee_bps, withdraw_fee_bps) in [(101, 0), (0, 101)] {
+        mollusk.process_and_validate_instruction(
+            &set_fees(admin, deposit_fee_bps, withdraw_fee_bps),
+            &tx_accounts(admin),
+            &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::FeeTooHigh.into()))],
+        );
+    }
+
+    let stranger = Pubkey::new_unique();
+    mollusk.process_and_validate_instruction(
+        &set_fees(stranger, 10, 10),
+        &tx_accounts(stranger),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))],
+    );
+}
+
+#[test]
+fn test_reconcile_credits_surplus() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 1_000_000,
+        ..Default::default()
+    });
+
+    // 500_000 was sent straight to the vault account
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Reconcile {}).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 1_500_000).into()),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::success()],
+    );
+
+    let vault_state_after: VaultState =
+        bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert_eq!(vault_state_after.deposited, 1_500_000, "Surplus should be credited to deposited");
+    // The credited surplus is chained like a deposit, so `deposited` can be replayed from the audit trail
+    let expected_hash = hashv(&[
+        &[0u8; 32],
+        &[AuditOp::Reconcile as u8],
+        &500_000u64.to_le_bytes(),
+        &mollusk.sysvars.clock.slot.to_le_bytes(),
+    ]);
+    assert_eq!(vault_state_after.audit_count, 1);
+    assert_eq!(vault_state_after.audit_hash, expected_hash.to_bytes(), "Reconcile should be chained into the audit hash");
+}
+
+#[test]
+fn test_withdraw_rejects_underfunded_vault() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // `deposited` claims more than the vault account holds
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 2_000_000,
+        ..Default::default()
+    });
+
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Withdraw { amount: 500_000 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (user_ata, get_token_account(&user, &token_mint, 0).into()),
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 1_000_000).into()),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultUnderfunded.into()))],
+    );
+}
+
+#[test]
+fn test_close_vault_requires_zero_balance() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, _) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CloseVault {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new(user, false), // rent_payer
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = |deposited: u64, paused: bool| vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, get_vault_state_account(&mollusk, &program_id, VaultState {
+            user,
+            mint: token_mint,
+            bump: state_bump,
+            bump_token_account: vault_account_bump,
+            deposited,
+            ..Default::default()
+        })),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, deposited).into()),
+        (token_mint, token_mint_account.clone().into()),
+        (token_program, token_program_account.clone()),
+        (system_program, system_account.clone()),
+        (config_pda, get_config_account(&mollusk, &program_id, paused).1),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // Funds left in the vault must be withdrawn (or swept) first
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts(1_000_000, false),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::NonZeroBalance.into()))],
+    );
+
+    // Closing is refused while the program is paused, like every other instruction that moves funds
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts(0, true),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::ProgramPaused.into()))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(&instruction, &tx_accounts(0, false), &[Check::success()]);
+    for key in [vault_state_pda, vault_account_pda] {
+        assert!(
+            result.get_account(&key).is_none() || result.get_account(&key).unwrap().lamports() == 0,
+            "Empty vault accounts should be closed"
+        );
+    }
+}
+
+#[test]
+fn test_migrate_vault_upgrades_baseline_layout() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (_, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // A vault as the baseline program wrote it: user, mint, deposited, bump, bump_token_account, padding
+    let mut legacy_data = Vec::with_capacity(VaultState::LEGACY_SPACE);
+    legacy_data.extend_from_slice(VaultState::DISCRIMINATOR);
+    legacy_data.extend_from_slice(user.as_ref());
+    legacy_data.extend_from_slice(token_mint.as_ref());
+    legacy_data.extend_from_slice(&1_000_000u64.to_le_bytes());
+    legacy_data.extend_from_slice(&[state_bump, vault_account_bump]);
+    legacy_data.extend_from_slice(&[0; 6]);
+    assert_eq!(legacy_data.len(), 88);
+    let mut legacy_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(legacy_data.len()),
+        legacy_data.len(),
+        &program_id,
+    );
+    legacy_account.data_as_mut_slice().copy_from_slice(&legacy_data);
+
+    let user_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+    // Handlers refuse the old layout instead of misreading it
+    mollusk.process_and_validate_instruction(
+        &Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::ExtendLock { unlock_ts: 1 }).data(),
+            vec![
+                AccountMeta::new_readonly(user, true),
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new_readonly(event_authority(&program_id), false),
+                AccountMeta::new_readonly(program_id, false), // program
+            ],
+        ),
+        &[
+            (user, user_account.clone()),
+            (vault_state_pda, legacy_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::UnsupportedVaultVersion.into()))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::MigrateVault {}).data(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(event_authority(&program_id), false),
+                AccountMeta::new_readonly(program_id, false), // program
+            ],
+        ),
+        &[
+            (user, user_account),
+            (vault_state_pda, legacy_account),
+            (system_program, system_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::success()],
+    );
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    assert_eq!(vault_state_after.data.len(), VaultState::SPACE);
+    assert_eq!(
+        vault_state_after.lamports,
+        mollusk.sysvars.rent.minimum_balance(VaultState::SPACE),
+        "Owner tops the account up to the new rent-exempt minimum"
+    );
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.version, anchor::VAULT_STATE_VERSION);
+    assert_eq!(vault_state_after.deposited, 1_000_000, "Existing fields are preserved");
+    let expected = VaultState {
+        user,
+        mint: token_mint,
+        deposited: 1_000_000,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        ..Default::default()
+    };
+    assert_eq!(
+        bytemuck::bytes_of(&vault_state_after),
+        bytemuck::bytes_of(&expected),
+        "Every field the baseline did not have starts zeroed"
+    );
+}
+
+#[test]
+fn test_close_vault_refunds_rent_payer() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // Someone other than the owner funded the vault's rent
+    let sponsor = Pubkey::new_unique();
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        rent_payer: sponsor,
+        ..Default::default()
+    });
+    let vault_token_account: Account = get_token_account(&vault_state_pda, &token_mint, 0).into();
+    let rent = vault_state_account.lamports + vault_token_account.lamports;
+
+    let close = |rent_payer: Pubkey| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CloseVault {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new(rent_payer, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let tx_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, vault_token_account),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (system_program, system_account),
+        (config_pda, config_account),
+        (sponsor, Account::new(0, 0, &system_program)),
+        (event_authority(&program_id), Account::default()),
+    ];
+
+    // The owner cannot redirect the refund to themselves
+    mollusk.process_and_validate_instruction(
+        &close(user),
+        &tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))],
+    );
+
+    mollusk.process_and_validate_instruction(
+        &close(sponsor),
+        &tx_accounts,
+        &[
+            Check::success(),
+            Check::account(&sponsor).lamports(rent).build(),
+            Check::account(&user).lamports(LAMPORTS_PER_SOL).build(),
+        ],
+    );
+}
+
+#[test]
+fn test_top_up_rent_restores_minimum() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // Both accounts are 1_000 lamports short, e.g. after a rent increase
+    let mut vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        ..Default::default()
+    });
+    vault_state_account.lamports -= 1_000;
+    let mut vault_token_account: Account = get_token_account(&vault_state_pda, &token_mint, 0).into();
+    vault_token_account.lamports = mollusk.sysvars.rent.minimum_balance(vault_token_account.data.len()) - 1_000;
+
+    // Permissionless: any payer can keep the vault alive
+    let payer = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::TopUpRent {}).data(),
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (payer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, vault_token_account),
+            (token_mint, token_mint_account.into()),
+            (system_program, system_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[
+            Check::success(),
+            Check::account(&payer).lamports(LAMPORTS_PER_SOL - 2_000).build(),
+        ],
+    );
+
+    let vault_state_after = result.get_account(&vault_state_pda).unwrap();
+    assert_eq!(vault_state_after.lamports, mollusk.sysvars.rent.minimum_balance(VaultState::SPACE));
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&vault_state_after.data[8..]);
+    assert_eq!(vault_state_after.rent_payer, Pubkey::default(), "Topping up does not take over the refund");
+}
+
+#[test]
+fn test_get_vault_info_returns_view() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let now = mollusk.sysvars.clock.unix_timestamp;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 4_000_000,
+        unlock_ts: now + 3_600,
+        withdraw_limit: 1_000_000,
+        withdraw_window: 86_400,
+        window_start: now,
+        window_used: 300_000,
+        ..Default::default()
+    });
+
+    // No signer: anyone, or another program via CPI, can read a vault
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::GetVaultInfo {}).data(),
+        vec![
+            AccountMeta::new_readonly(vault_state_pda, false),
+            AccountMeta::new_readonly(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+        ],
+    );
+    let result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (vault_state_pda, vault_state_account),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 4_500_000).into()),
+            (token_mint, token_mint_account.into()),
+        ],
+        &[Check::success()],
+    );
+
+    let info = VaultInfo::try_from_slice(&result.return_data).unwrap();
+    assert_eq!(info.version, anchor::VAULT_STATE_VERSION);
+    assert_eq!(info.owner, user);
+    assert_eq!(info.mint, token_mint);
+    assert_eq!(info.deposited, 4_000_000);
+    assert_eq!(info.vault_balance, 4_500_000, "Balance comes from the token account, surplus included");
+    assert_eq!(info.decimals, 9);
+    assert!(info.locked);
+    assert_eq!(info.withdraw_limit_remaining, 700_000);
+}
+
+#[test]
+fn test_batch_withdraw_is_atomic_across_vaults() {
+    let (mollusk, program_id, user, _, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let existing_deposit = 5_000_000;
+    let now = mollusk.sysvars.clock.unix_timestamp;
+
+    // Two vaults of the same owner over different mints; `locked` sets the second one's unlock date
+    let batch_accounts = |locked: bool| {
+        let mut metas = vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ];
+        let mut tx_accounts = vec![
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ];
+        let mut vaults = Vec::new();
+        for index in 0..2 {
+            let token_mint = Pubkey::new_unique();
+            let (vault_state_pda, state_bump) =
+                Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+            let (vault_account_pda, vault_account_bump) =
+                Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+            let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+            let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+                user,
+                mint: token_mint,
+                bump: state_bump,
+                bump_token_account: vault_account_bump,
+                deposited: existing_deposit,
+                unlock_ts: if locked && index == 1 { now + 3_600 } else { 0 },
+                ..Default::default()
+            });
+
+            metas.extend([
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new(vault_account_pda, false),
+                AccountMeta::new(user_ata, false),
+                AccountMeta::new_readonly(token_mint, false),
+            ]);
+            tx_accounts.extend([
+                (vault_state_pda, vault_state_account),
+                (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+                (user_ata, get_token_account(&user, &token_mint, 0).into()),
+                (token_mint, get_mint_account(&user, existing_deposit).into()),
+            ]);
+            vaults.push((vault_state_pda, user_ata));
+        }
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::BatchWithdraw { amounts: vec![1_000_000, 2_000_000] }).data(),
+            metas,
+        );
+        (instruction, tx_accounts, vaults)
+    };
+
+    let (instruction, tx_accounts, vaults) = batch_accounts(false);
+    let result = mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()]);
+    for ((vault_state_pda, user_ata), amount) in vaults.into_iter().zip([1_000_000u64, 2_000_000]) {
+        let vault_state_after: VaultState =
+            bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+        assert_eq!(vault_state_after.deposited, existing_deposit - amount);
+        assert_eq!(vault_state_after.audit_count, 1, "Each vault records its own withdraw");
+        let user_balance = u64::from_le_bytes(result.get_account(&user_ata).unwrap().data[64..72].try_into().unwrap());
+        assert_eq!(user_balance, amount);
+    }
+
+    // One vault failing its checks fails the whole batch
+    let (instruction, tx_accounts, _) = batch_accounts(true);
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultLocked.into()))],
+    );
+}
+
+#[test]
+fn test_batch_deposit_checks_each_group() {
+    let (mollusk, program_id, user, _, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let user_balance = 5_000_000;
+    let amounts = [1_000_000u64, 2_000_000];
+
+    // Two empty vaults of the same owner over different mints
+    let mut metas = vec![
+        AccountMeta::new_readonly(user, true),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+    let mut tx_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account.clone()),
+        (event_authority(&program_id), Account::default()),
+    ];
+    let mut vaults = Vec::new();
+    for _ in 0..2 {
+        let token_mint = Pubkey::new_unique();
+        let (vault_state_pda, state_bump) =
+            Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+        let (vault_account_pda, vault_account_bump) =
+            Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+        let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+        let vault_state = VaultState {
+            user,
+            mint: token_mint,
+            bump: state_bump,
+            bump_token_account: vault_account_bump,
+            ..Default::default()
+        };
+
+        metas.extend([
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new_readonly(token_mint, false),
+        ]);
+        tx_accounts.extend([
+            (vault_state_pda, get_vault_state_account(&mollusk, &program_id, vault_state)),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, 0).into()),
+            (user_ata, get_token_account(&user, &token_mint, user_balance).into()),
+            (token_mint, get_mint_account(&user, user_balance).into()),
+        ]);
+        vaults.push((vault_state_pda, vault_state, user_ata, token_mint));
+    }
+    let instruction = |metas: Vec<AccountMeta>| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::BatchDeposit { amounts: amounts.to_vec() }).data(),
+        metas,
+    );
+
+    let result = mollusk.process_and_validate_instruction(&instruction(metas.clone()), &tx_accounts, &[Check::success()]);
+    for ((vault_state_pda, _, user_ata, _), amount) in vaults.iter().zip(amounts) {
+        let vault_state_after: VaultState =
+            bytemuck::pod_read_unaligned(&result.get_account(vault_state_pda).unwrap().data[8..]);
+        assert_eq!(vault_state_after.deposited, amount, "Each vault is credited its own amount");
+        assert_eq!(vault_state_after.audit_count, 1, "Each vault records its own deposit");
+        let balance = u64::from_le_bytes(result.get_account(user_ata).unwrap().data[64..72].try_into().unwrap());
+        assert_eq!(balance, user_balance - amount);
+    }
+
+    // The second group's accounts sit at these offsets
+    let (vault_state_pda, vault_state, user_ata, token_mint) = vaults[1];
+    let (state_tx, ata_tx, mint_meta) = (8, 10, 5 + 4 + 3);
+    let unauthorized = [Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))];
+    let stranger = Pubkey::new_unique();
+
+    // A vault owned by someone else
+    let mut wrong_vault_owner = tx_accounts.clone();
+    wrong_vault_owner[state_tx] = (
+        vault_state_pda,
+        get_vault_state_account(&mollusk, &program_id, VaultState { user: stranger, ..vault_state }),
+    );
+    mollusk.process_and_validate_instruction(&instruction(metas.clone()), &wrong_vault_owner, &unauthorized);
+
+    // A source token account owned by someone else
+    let mut wrong_account_owner = tx_accounts.clone();
+    wrong_account_owner[ata_tx] = (user_ata, get_token_account(&stranger, &token_mint, user_balance).into());
+    mollusk.process_and_validate_instruction(&instruction(metas.clone()), &wrong_account_owner, &unauthorized);
+
+    // A mint other than the vault's
+    let other_mint = Pubkey::new_unique();
+    let mut mismatched_metas = metas.clone();
+    mismatched_metas[mint_meta] = AccountMeta::new_readonly(other_mint, false);
+    let mut mismatched_accounts = tx_accounts.clone();
+    mismatched_accounts.push((other_mint, get_mint_account(&user, user_balance).into()));
+    mollusk.process_and_validate_instruction(
+        &instruction(mismatched_metas),
+        &mismatched_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::InvalidMint.into()))],
+    );
+}
+
+#[test]
+fn test_payout_splits_one_withdrawal() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+
+    // 0.5% withdraw fee
+    let treasury = Pubkey::new_unique();
+    let (config_pda, config_bump) = Pubkey::find_program_address(&["config".as_ref()], &program_id);
+    let config_account = get_zero_copy_account(&mollusk, &program_id, &ProgramConfig {
+        admin: Pubkey::new_unique(),
+        bump: config_bump,
+        withdraw_fee_bps: 50,
+        treasury,
+        ..Default::default()
+    });
+    let treasury_ata = spl_associated_token_account::get_associated_token_address(&treasury, &token_mint);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 10_000_000;
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        ..Default::default()
+    });
+
+    let amounts = vec![1_000_000u64, 2_500_000, 500_000];
+    let recipients: Vec<Pubkey> = amounts
+        .iter()
+        .map(|_| spl_associated_token_account::get_associated_token_address(&Pubkey::new_unique(), &token_mint))
+        .collect();
+
+    let mut ix_accounts = vec![
+        AccountMeta::new_readonly(user, true),
+        AccountMeta::new(vault_state_pda, false),
+        AccountMeta::new(vault_account_pda, false),
+        AccountMeta::new_readonly(token_mint, false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+        AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new(treasury_ata, false),
+        AccountMeta::new_readonly(program_id, false), // allowlist (none)
+        AccountMeta::new_readonly(event_authority(&program_id), false),
+        AccountMeta::new_readonly(program_id, false), // program
+    ];
+    ix_accounts.extend(recipients.iter().map(|key| AccountMeta::new(*key, false)));
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Payout { amounts: amounts.clone() }).data(),
+        ix_accounts,
+    );
+
+    let mut tx_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state_account),
+        (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+        (token_mint, token_mint_account.into()),
+        (token_program, token_program_account.clone()),
+        (config_pda, config_account),
+        (treasury_ata, get_token_account(&treasury, &token_mint, 0).into()),
+        (event_authority(&program_id), Account::default()),
+    ];
+    // Token accounts are owned by their wallets, not the vault owner
+    tx_accounts.extend(recipients.iter().map(|key| (*key, get_token_account(&Pubkey::new_unique(), &token_mint, 0).into())));
+
+    let result = mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()]);
+
+    let token_amount = |key: &Pubkey| {
+        u64::from_le_bytes(result.get_account(key).unwrap().data[64..72].try_into().unwrap())
+    };
+    // Amounts are gross, as in withdraw: each recipient's fee comes out of their own amount
+    for (recipient, amount) in recipients.iter().zip(&amounts) {
+        assert_eq!(token_amount(recipient), amount - amount * 50 / 10_000, "Each recipient gets their amount net of the fee");
+    }
+    let total: u64 = amounts.iter().sum();
+    assert_eq!(token_amount(&treasury_ata), 5_000 + 12_500 + 2_500, "Treasury receives the fees");
+    assert_eq!(token_amount(&vault_account_pda), existing_deposit - total, "Nothing is charged on top");
+    let vault_state_after: VaultState =
+        bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert_eq!(vault_state_after.deposited, existing_deposit - total, "Deposited drops by the gross total");
+    assert_eq!(vault_state_after.audit_count, 1, "The payout is one withdraw in the audit chain");
+    assert_eq!(vault_state_after.event_seq, 1, "The payout emits one event, so one sequence number");
+}
+
+#[test]
+fn test_withdraw_allowlist_gates_destinations() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+    let (allowlist_pda, allowlist_bump) =
+        Pubkey::find_program_address(&["allowlist".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let existing_deposit = 5_000_000;
+    let now = mollusk.sysvars.clock.unix_timestamp;
+    let vault_state = VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        ..Default::default()
+    };
+
+    // A cold wallet that is active, and one added moments ago that is still waiting out the delay
+    let cold_wallet = Pubkey::new_unique();
+    let pending_wallet = Pubkey::new_unique();
+    let mut allowlist = WithdrawAllowlist {
+        vault: vault_state_pda,
+        delay: 86_400,
+        count: 2,
+        bump: allowlist_bump,
+        ..Default::default()
+    };
+    allowlist.destinations[..2].copy_from_slice(&[cold_wallet, pending_wallet]);
+    allowlist.active_ts[..2].copy_from_slice(&[now - 1, now + 86_400]);
+    let allowlist_account = get_zero_copy_account(&mollusk, &program_id, &allowlist);
+
+    let withdraw = |destination_owner: &Pubkey, allowlist_enabled: bool, with_allowlist: bool| {
+        let destination = spl_associated_token_account::get_associated_token_address(destination_owner, &token_mint);
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &(anchor::instruction::Withdraw { amount: 1_000_000 }).data(),
+            vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(vault_state_pda, false),
+                AccountMeta::new(vault_account_pda, false),
+                AccountMeta::new_readonly(token_mint, false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+                AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+                AccountMeta::new_readonly(config_pda, false),
+                AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+                AccountMeta::new_readonly(if with_allowlist { allowlist_pda } else { program_id }, false),
+                AccountMeta::new_readonly(event_authority(&program_id), false),
+                AccountMeta::new_readonly(program_id, false), // program
+            ],
+        );
+        let tx_accounts = vec![
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (destination, get_token_account(destination_owner, &token_mint, 0).into()),
+            (
+                vault_state_pda,
+                get_vault_state_account(&mollusk, &program_id, VaultState {
+                    allowlist_enabled: allowlist_enabled as u8,
+                    ..vault_state
+                }),
+            ),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+            (token_mint, token_mint_account.clone().into()),
+            (token_program, token_program_account.clone()),
+            (config_pda, config_account.clone()),
+            (allowlist_pda, allowlist_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ];
+        (instruction, tx_accounts)
+    };
+    let expect_err = |code: anchor::error::ErrorCode| [Check::err(ProgramError::Custom(code.into()))];
+
+    // Without an allowlist only the owner's own accounts can receive
+    let (instruction, tx_accounts) = withdraw(&cold_wallet, false, false);
+    mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts,
+        &[Check::err(ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintTokenOwner.into()))],
+    );
+
+    // Once enabled, the owner's own account is refused like any other unlisted destination
+    let (instruction, tx_accounts) = withdraw(&user, true, true);
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &expect_err(anchor::error::ErrorCode::DestinationNotAllowed));
+
+    let (instruction, tx_accounts) = withdraw(&pending_wallet, true, true);
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &expect_err(anchor::error::ErrorCode::DestinationNotAllowed));
+
+    let (instruction, tx_accounts) = withdraw(&cold_wallet, true, false);
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &expect_err(anchor::error::ErrorCode::MissingAllowlistAccount));
+
+    let (instruction, mut tx_accounts) = withdraw(&cold_wallet, true, true);
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()]);
+
+    // Removing the cold wallet refuses it at once
+    let remove_instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::RemoveAllowedDestination { destination: cold_wallet }).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(vault_state_pda, false),
+            AccountMeta::new(allowlist_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let result = mollusk.process_instruction(
+        &remove_instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (
+                vault_state_pda,
+                get_vault_state_account(&mollusk, &program_id, VaultState { allowlist_enabled: 1, ..vault_state }),
+            ),
+            (allowlist_pda, allowlist_account.clone()),
+            (event_authority(&program_id), Account::default()),
+        ],
+    );
+    assert!(!result.program_result.is_err(), "Remove allowed destination instruction failed");
+
+    let allowlist_index = tx_accounts.iter().position(|(key, _)| *key == allowlist_pda).unwrap();
+    tx_accounts[allowlist_index].1 = result.get_account(&allowlist_pda).unwrap().clone();
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &expect_err(anchor::error::ErrorCode::DestinationNotAllowed));
+}
+
+#[test]
+fn test_freeze_blocks_withdraw_until_unfrozen() {
+    let (mollusk, program_id, user, token_mint, token_mint_account, user_token_account) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let guardian = Pubkey::new_unique();
+    let existing_deposit = 5_000_000;
+    let vault_state = VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: existing_deposit,
+        guardian,
+        ..Default::default()
+    };
+
+    // The guardian freezes; a key that is neither owner nor guardian cannot
+    let freeze = |authority: Pubkey| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::FreezeVault {}).data(),
+        vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let stranger = Pubkey::new_unique();
+    let freeze_accounts = |authority: Pubkey| vec![
+        (authority, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, get_vault_state_account(&mollusk, &program_id, vault_state)),
+        (event_authority(&program_id), Account::default()),
+    ];
+    mollusk.process_and_validate_instruction(
+        &freeze(stranger),
+        &freeze_accounts(stranger),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))],
+    );
+
+    let result = mollusk.process_instruction(&freeze(guardian), &freeze_accounts(guardian));
+    assert!(!result.program_result.is_err(), "Freeze vault instruction failed");
+    let frozen_state: VaultState = bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert!(frozen_state.is_frozen());
+    assert_eq!(frozen_state.unfreeze_ts, mollusk.sysvars.clock.unix_timestamp + anchor::UNFREEZE_DELAY);
+
+    // Outflows are refused while frozen
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user, &token_mint);
+    let withdraw = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::Withdraw { amount: 1_000_000 }).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_ata, false),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(program_id, false), // receipt_mint (none)
+            AccountMeta::new_readonly(program_id, false), // user_receipt_account (none)
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(program_id, false), // treasury_account (none)
+            AccountMeta::new_readonly(program_id, false), // allowlist (none)
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    mollusk.process_and_validate_instruction(
+        &withdraw,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (user_ata, user_token_account.into()),
+            (vault_state_pda, get_vault_state_account(&mollusk, &program_id, frozen_state)),
+            (vault_account_pda, get_token_account(&vault_state_pda, &token_mint, existing_deposit).into()),
+            (token_mint, token_mint_account.into()),
+            (token_program, token_program_account),
+            (config_pda, config_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultFrozen.into()))],
+    );
+
+    // Before the delay the owner needs the guardian's signature, afterwards the owner is enough
+    let unfreeze = |with_guardian: bool| Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::UnfreezeVault {}).data(),
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            if with_guardian {
+                AccountMeta::new_readonly(guardian, true)
+            } else {
+                AccountMeta::new_readonly(program_id, false) // guardian (none)
+            },
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let unfreeze_accounts = vec![
+        (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, get_vault_state_account(&mollusk, &program_id, frozen_state)),
+        (guardian, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (event_authority(&program_id), Account::default()),
+    ];
+    mollusk.process_and_validate_instruction(
+        &unfreeze(false),
+        &unfreeze_accounts,
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::VaultFrozen.into()))],
+    );
+
+    let result = mollusk.process_instruction(&unfreeze(true), &unfreeze_accounts);
+    assert!(!result.program_result.is_err(), "Guardian co-signed unfreeze failed");
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert!(!vault_state_after.is_frozen());
+    assert_eq!(vault_state_after.unfreeze_ts, 0);
+
+    let mut mollusk = mollusk;
+    mollusk.sysvars.clock.unix_timestamp = frozen_state.unfreeze_ts;
+    mollusk.process_and_validate_instruction(&unfreeze(false), &unfreeze_accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_set_guardian_lets_guardian_freeze() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (system_program, _) = program::keyed_account_for_system_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (_, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    let vault_state_account = get_vault_state_account(&mollusk, &program_id, VaultState {
+        user,
+        mint: token_mint,
+        bump: state_bump,
+        bump_token_account: vault_account_bump,
+        deposited: 5_000_000,
+        ..Default::default()
+    });
+
+    let guardian = Pubkey::new_unique();
+    let signed = |signer: Pubkey, data: Vec<u8>| Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+    let tx_accounts = |signer: Pubkey, vault_state: Account| vec![
+        (signer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_state_pda, vault_state),
+        (event_authority(&program_id), Account::default()),
+    ];
+    let set_guardian = (anchor::instruction::SetGuardian { guardian }).data();
+
+    // The vault PDA is derived from the signer, so nobody else can name a guardian
+    let stranger = Pubkey::new_unique();
+    mollusk.process_and_validate_instruction(
+        &signed(stranger, set_guardian.clone()),
+        &tx_accounts(stranger, vault_state_account.clone()),
+        &[Check::err(ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into()))],
+    );
+
+    // Before it is set, the guardian cannot freeze
+    let freeze = (anchor::instruction::FreezeVault {}).data();
+    mollusk.process_and_validate_instruction(
+        &signed(guardian, freeze.clone()),
+        &tx_accounts(guardian, vault_state_account.clone()),
+        &[Check::err(ProgramError::Custom(anchor::error::ErrorCode::Unauthorized.into()))],
+    );
+
+    let result = mollusk.process_instruction(&signed(user, set_guardian), &tx_accounts(user, vault_state_account));
+    assert!(!result.program_result.is_err(), "Set guardian instruction failed");
+    let guarded_account = result.get_account(&vault_state_pda).unwrap().clone();
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&guarded_account.data[8..]);
+    assert_eq!(vault_state_after.guardian, guardian);
+
+    let result = mollusk.process_instruction(&signed(guardian, freeze), &tx_accounts(guardian, guarded_account));
+    assert!(!result.program_result.is_err(), "Freeze vault instruction failed");
+    let vault_state_after: VaultState = bytemuck::pod_read_unaligned(&result.get_account(&vault_state_pda).unwrap().data[8..]);
+    assert!(vault_state_after.is_frozen(), "The guardian should be able to freeze the vault");
+    assert_eq!(vault_state_after.unfreeze_ts, mollusk.sysvars.clock.unix_timestamp + anchor::UNFREEZE_DELAY);
+}
+
+#[test]
+fn test_close_vault_harvests_withheld_transfer_fees() {
+    let (mollusk, program_id, user, token_mint, _, _) = init_mollusk();
+    let (config_pda, config_account) = get_config_account(&mollusk, &program_id, false);
+
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+    let (token_program, token_program_account) = keyed_account_for_token_2022_program();
+
+    let (vault_state_pda, state_bump) =
+        Pubkey::find_program_address(&["vault".as_ref(), user.as_ref(), token_mint.as_ref()], &program_id);
+    let (vault_account_pda, vault_account_bump) =
+        Pubkey::find_program_address(&["vault_account".as_ref(), vault_state_pda.as_ref()], &program_id);
+
+    // Empty vault that still holds the fees withheld from earlier deposits
+    let mut vault_token_account = get_token_account_2022(&vault_state_pda, &token_mint, 0, true);
+    StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack(vault_token_account.data_as_mut_slice())
+        .unwrap()
+        .get_extension_mut::<TransferFeeAmount>()
+        .unwrap()
+        .withheld_amount = 5_000.into();
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &(anchor::instruction::CloseVault {}).data(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(vault_state_pda, false),
+            AccountMeta::new(vault_account_pda, false),
+            AccountMeta::new(token_mint, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new(user, false), // rent_payer
+            AccountMeta::new_readonly(event_authority(&program_id), false),
+            AccountMeta::new_readonly(program_id, false), // program
+        ],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (user, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (vault_state_pda, get_vault_state_account(&mollusk, &program_id, VaultState {
+                user,
+                mint: token_mint,
+                bump: state_bump,
+                bump_token_account: vault_account_bump,
+                ..Default::default()
+            })),
+            (vault_account_pda, vault_token_account.into()),
+            (token_mint, get_mint_account_2022(&user, 5_000_000_000, Some(100)).into()),
+            (token_program, token_program_account),
+            (system_program, system_account),
+            (config_pda, config_account),
+            (event_authority(&program_id), Account::default()),
+        ],
+        &[Check::success()],
+    );
+
+    assert!(
+        result.get_account(&vault_account_pda).is_none() || result.get_account(&vault_account_pda).unwrap().lamports() == 0,
+        "Vault account closes once its withheld fees are harvested"
+    );
+    let mint_after = result.get_account(&token_mint).unwrap();
+    let mint_after = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_after.data).unwrap();
+    assert_eq!(
+        u64::from(mint_after.get_extension::<TransferFeeConfig>().unwrap().withheld_amount),
+        5_000,
+        "Withheld fees move to the mint"
+    );
+}
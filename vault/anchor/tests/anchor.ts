@@ -16,6 +16,8 @@ describe("anchor", () => {
   let userTokenAccount: anchor.web3.PublicKey;
   let vaultState: anchor.web3.PublicKey;
   let vaultAccount: anchor.web3.PublicKey;
+  let config: anchor.web3.PublicKey;
+  let eventAuthority: anchor.web3.PublicKey;
 
   before(async () => {
     user = anchor.web3.Keypair.generate();
@@ -62,17 +64,31 @@ describe("anchor", () => {
       [Buffer.from("vault_account"), vaultState.toBuffer()],
       program.programId
     );
+    [config] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    // Signs the self-CPI that vault events are emitted through
+    [eventAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
   });
 
   it("Initializes the vault", async () => {
-    await program.methods.initialize().accountsStrict({
+    await program.methods.initialize(new anchor.BN(0)).accountsStrict({
       user: user.publicKey,
+      payer: user.publicKey,
       vaultState,
       vaultAccount,
       mint,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      // Bootstrapped with `user` as admin on a fresh validator; its mint policy is checked
+      config,
+      eventAuthority,
+      program: program.programId,
     }).signers([user]).rpc();
     // Check vault state account exists
     const vaultStateAcc = await program.account.vaultState.fetch(vaultState);
@@ -80,6 +96,25 @@ describe("anchor", () => {
     assert.ok(vaultStateAcc.mint.equals(mint));
   });
 
+  it("Sets the mint policy", async () => {
+    const policy = {
+      rejectFreezeAuthority: true,
+      minDecimals: 0,
+      maxDecimals: 9,
+      rejectPermanentDelegate: true,
+      rejectNonTransferable: true,
+      rejectTransferHook: true,
+      rejectDefaultFrozen: true,
+    };
+    // `user` bootstrapped the config above, so it is the admin
+    await program.methods.setMintPolicy(policy).accountsStrict({
+      admin: user.publicKey,
+      config,
+    }).signers([user]).rpc();
+    const configAcc = await program.account.programConfig.fetch(config);
+    assert.equal(configAcc.maxDecimals, 9);
+  });
+
   it("Deposits tokens", async () => {
     await program.methods.deposit(new anchor.BN(100_000)).accountsStrict({
       user: user.publicKey,
@@ -88,6 +123,12 @@ describe("anchor", () => {
       vaultAccount,
       mint,
       tokenProgram: TOKEN_PROGRAM_ID,
+      receiptMint: null,
+      userReceiptAccount: null,
+      config,
+      treasuryAccount: null,
+      eventAuthority,
+      program: program.programId,
     }).signers([user]).rpc();
     // Check vault account balance
     const vaultAcc = await getAccount(connection, vaultAccount);
@@ -105,6 +146,13 @@ describe("anchor", () => {
       vaultAccount,
       mint,
       tokenProgram: TOKEN_PROGRAM_ID,
+      receiptMint: null,
+      userReceiptAccount: null,
+      config,
+      treasuryAccount: null,
+      allowlist: null,
+      eventAuthority,
+      program: program.programId,
     }).signers([user]).rpc();
     // Check vault account balance
     const vaultAcc = await getAccount(connection, vaultAccount);
@@ -114,8 +162,8 @@ describe("anchor", () => {
     assert.equal(Number(vaultStateAcc.deposited), 50_000);
   });
 
-  it("Closes the vault", async () => {
-    await program.methods.closeVault().accountsStrict({
+  it("Sweeps and closes the vault", async () => {
+    await program.methods.sweepAndClose().accountsStrict({
       user: user.publicKey,
       userAccount: userTokenAccount,
       vaultState,
@@ -123,6 +171,12 @@ describe("anchor", () => {
       mint,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
+      config,
+      treasuryAccount: null,
+      allowlist: null,
+      rentPayer: user.publicKey,
+      eventAuthority,
+      program: program.programId,
     }).signers([user]).rpc();
     // Vault account should be closed (throws if not found)
     try {